@@ -1,87 +1,175 @@
-use crate::builders::{FILES_DIR, FONTS_DIR};
-use chrono::Local;
-use genpdf::{
-    elements::*, fonts, style, Alignment, Document, Element as _, Margins, PaperSize, Scale,
-    SimplePageDecorator,
-};
-use image::io::Reader;
-use pdfshrink::gs_command;
-use std::io::{self, Cursor, Write};
-use std::{ffi::OsStr, path::PathBuf};
-use tempfile;
-
+use crate::builders::format::{self, Format};
+use crate::builders::pages::{self, Decorator, Saved};
+use crate::builders::{docx, html, tex, text, Settings, FILES_DIR};
+use genpdf::{elements::*, style, Alignment, Document, Element as _, Scale};
+use image::{io::Reader, DynamicImage, GenericImageView, Rgb, RgbImage};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use crate::builders::utils::{contact_links, ContactItem};
 use crate::helpers;
 
+/// The most room a signature takes in the closing block, in mm.
+const SIGNATURE_HEIGHT: f64 = 15.0;
+const SIGNATURE_WIDTH: f64 = 60.0;
+
+/// Reads a signature image for `Settings::signature`. Transparency is
+/// flattened onto white, since genpdf can't draw an alpha channel.
+pub fn signature(path: &Path) -> Result<DynamicImage, String> {
+    let image = Reader::open(path)
+        .map_err(|e| e.to_string())?
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?
+        .decode()
+        .map_err(|e| e.to_string())?;
+    if !image.color().has_alpha() {
+        return Ok(image);
+    }
+    let rgba = image.to_rgba8();
+    let flattened = RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let pixel = rgba.get_pixel(x, y);
+        let alpha = u16::from(pixel[3]);
+        Rgb([0, 1, 2].map(|i| ((u16::from(pixel[i]) * alpha + 255 * (255 - alpha)) / 255) as u8))
+    });
+    Ok(DynamicImage::ImageRgb8(flattened))
+}
+
+/// A size in pixels as genpdf draws it, at 300 dpi, in mm.
+fn mm(px: u32) -> f64 {
+    f64::from(px) * 25.4 / 300.0
+}
+
+/// The scale that fits a `width` by `height` pixel image into the
+/// signature's space.
+fn signature_scale(width: u32, height: u32) -> f64 {
+    (SIGNATURE_HEIGHT / mm(height)).min(SIGNATURE_WIDTH / mm(width))
+}
+
+/// `image` scaled to fit the signature's space, keeping its proportions.
+fn signature_image(image: &DynamicImage) -> Option<Image> {
+    let (width, height) = image.dimensions();
+    let scale = signature_scale(width, height);
+    Image::from_dynamic_image(image.clone())
+        .ok()
+        .map(|image| image.with_scale(Scale::new(scale, scale)))
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn build(
     name: String,
     company: String,
     location: Option<String>,
-    position: Option<String>,
+    hiring_manager: Option<String>,
+    body: Vec<String>,
+    contact: Vec<ContactItem>,
+    settings: Settings,
     out: PathBuf,
-) {
-    let output: PathBuf;
-
-    if !(out.extension().is_none() || out.extension() == Some(OsStr::new("pdf"))) {
-        output = PathBuf::from(format!("{}.pdf", out.display()).to_owned());
-    } else {
-        output = PathBuf::from(out).to_owned();
+) -> Result<Saved, String> {
+    let location = location.as_deref();
+    let hiring_manager = hiring_manager.as_deref();
+    match settings.format {
+        Format::Pdf => {
+            let theme = &settings.theme;
+            let decorator = Decorator::new(
+                &settings.pages.cover_letter,
+                &name,
+                &contact,
+                theme,
+                theme.layout.letter,
+            );
+            let rendered = pages::render_numbered(
+                || {
+                    document(
+                        &name,
+                        &company,
+                        location,
+                        hiring_manager,
+                        &body,
+                        &contact,
+                        &settings,
+                    )
+                },
+                decorator,
+                &settings.metadata,
+            )?;
+            pages::save(&rendered.pdf, out, settings.optimize)
+        }
+        Format::Html => format::save(
+            html::cover_letter(
+                &name,
+                &company,
+                location,
+                hiring_manager,
+                &body,
+                &contact,
+                &settings,
+            )
+            .as_bytes(),
+            out,
+            settings.format,
+        ),
+        Format::Docx => format::save(
+            &docx::cover_letter(
+                &name,
+                &company,
+                location,
+                hiring_manager,
+                &body,
+                &contact,
+                &settings,
+            )?,
+            out,
+            settings.format,
+        ),
+        Format::Tex => format::save(
+            tex::cover_letter(
+                &name,
+                &company,
+                location,
+                hiring_manager,
+                &body,
+                &contact,
+                &settings,
+            )
+            .as_bytes(),
+            out,
+            settings.format,
+        ),
+        Format::Txt | Format::Md => format::save(
+            text::cover_letter(
+                &name,
+                &company,
+                location,
+                hiring_manager,
+                &body,
+                &contact,
+                settings.format == Format::Md,
+            )
+            .as_bytes(),
+            out,
+            settings.format,
+        ),
     }
+}
 
-    // Load a font from the file system
-    let regular = fonts::FontData::new(
-        FONTS_DIR
-            .get_file("Rubik/Rubik-Regular.ttf")
-            .unwrap()
-            .contents()
-            .to_vec(),
-        None,
-    )
-    .unwrap();
-    let bold = fonts::FontData::new(
-        FONTS_DIR
-            .get_file("Rubik/Rubik-Bold.ttf")
-            .unwrap()
-            .contents()
-            .to_vec(),
-        None,
-    )
-    .unwrap();
-    let italic = fonts::FontData::new(
-        FONTS_DIR
-            .get_file("Rubik/Rubik-Italic.ttf")
-            .unwrap()
-            .contents()
-            .to_vec(),
-        None,
-    )
-    .unwrap();
-    let bold_italic = fonts::FontData::new(
-        FONTS_DIR
-            .get_file("Rubik/Rubik-BoldItalic.ttf")
-            .unwrap()
-            .contents()
-            .to_vec(),
-        None,
-    )
-    .unwrap();
-    let font_family = fonts::FontFamily {
-        regular,
-        bold,
-        italic,
-        bold_italic,
-    };
+/// Lays out the cover letter, ready to render.
+pub fn document(
+    name: &str,
+    company: &str,
+    location: Option<&str>,
+    hiring_manager: Option<&str>,
+    body: &[String],
+    contact: &[ContactItem],
+    settings: &Settings,
+) -> Document {
     // Create a document and set the default font family
-    let mut doc = Document::new(font_family);
-    doc.set_font_size(10);
-    doc.set_line_spacing(1.3);
-    doc.set_paper_size(PaperSize::Letter);
+    let theme = &settings.theme;
+    let mut doc = Document::new(settings.font_family.clone());
+    doc.set_font_size(theme.font_sizes.letter_body);
+    doc.set_line_spacing(theme.layout.letter_line_spacing);
+    doc.set_paper_size(settings.paper);
     // Change the default settings
-    doc.set_title("Cover Letter");
-    // Set margins
-
-    // Customize the pages
-    let decorator = SimplePageDecorator::new();
-    doc.set_page_decorator(decorator);
+    doc.set_title(&settings.metadata.title);
 
     // Add one or more elements
 
@@ -94,31 +182,30 @@ pub fn build(
         para.styled(
             style::Style::new()
                 .bold()
-                .with_color(style::Color::Rgb(129, 173, 187))
-                .with_font_size(8)
+                .with_color(theme.colours.accent.into())
+                .with_font_size(theme.font_sizes.label)
                 .with_line_spacing(1.5),
         ),
     );
 
-    let cap: String = helpers::capitalize(&name);
+    let cap: String = helpers::capitalize(name);
 
     let mut para = Paragraph::default();
     para.set_alignment(Alignment::Center);
     para.push(style::StyledString::new(
         cap,
-        style::Style::new().with_font_size(32),
+        style::Style::new().with_font_size(theme.font_sizes.letter_name),
     ));
     layout.push(
         para.styled(
             style::Style::new()
                 .bold()
-                .with_color(style::Color::Rgb(26, 92, 113))
-                .with_font_size(26),
+                .with_color(theme.colours.name.into())
+                .with_font_size(theme.font_sizes.letter_name),
         ),
     );
 
-    let margins = Margins::trbl(4, 44, 0, 44);
-    let ele = PaddedElement::new(layout, margins);
+    let ele = PaddedElement::new(layout, theme.layout.letter_header);
     doc.push(ele);
 
     doc.push(Break::new(0.5));
@@ -134,144 +221,205 @@ pub fn build(
     )
     .expect("Failed to load test image")
     .with_alignment(genpdf::Alignment::Center)
-    .with_scale(Scale { x: 1.0, y: 1.0 }); // Center the image on the page.
+    .with_scale(settings.paper.scale(1.0, 1.0)); // Center the image on the page.
     doc.push(image);
     doc.push(Break::new(1));
 
     let mut layout = LinearLayout::vertical();
 
-    let now = format!("{}", Local::now().format("%B %e, %Y"));
-    let para = Paragraph::new(now);
+    let para = Paragraph::new(helpers::today());
     layout.push(para);
 
     layout.push(Break::new(1));
 
-    let letter_head_to = format!("{}", company.clone());
-    let para = Paragraph::new(letter_head_to);
+    let para = Paragraph::new(company);
     layout.push(para);
 
-    if location.is_some() {
-        let letter_head_at = format!("{}", location.unwrap());
-        let para = Paragraph::new(letter_head_at);
+    if let Some(location) = location {
+        let para = Paragraph::new(location);
         layout.push(para);
     }
 
     layout.push(Break::new(1));
 
-    let greeting = format!("Dear {},", company.clone());
+    let greeting = format!("Dear {},", hiring_manager.unwrap_or(company));
     let para = Paragraph::new(greeting);
     layout.push(para);
 
     layout.push(Break::new(1));
 
-    let role = match position {
-        Some(position) => format!("{} opportunity at", position),
-        None => "opportunity to work at".to_string(),
-    };
-
-    let para1 = format!("I am writing to express my sincere interest in the {} {}. My journey in the realm of machine learning and software engineering has ignited a deep passion for innovation and problem-solving. I am excited about the prospect of contributing to your dynamic team's achievements.", role, company.clone());
-    let para = Paragraph::new(para1);
-    layout.push(para);
-
-    layout.push(Break::new(1));
-
-    let para2 = "My background is firmly rooted in machine learning, where I have designed and developed a range of models, from object recognition to genetic algorithms. These experiences have honed my ability to tackle complex challenges with creative solutions. One notable achievement was at HHMI, where I led a project resulting in a remarkable 50x performance improvement in ETL processes. This optimization facilitated the integration of facial recognition outputs with neuron segmentation data, enabling groundbreaking joint analyses of behavioral and microscopy data.";
-    let para = Paragraph::new(para2);
-    layout.push(para);
-
-    layout.push(Break::new(1));
-
-    let para3 = format!("My journey through computational publications, bioinformatics education, and comprehensive software engineering training has equipped me with a unique skill set. I am impressed by {}’s reputation for innovation and its unwavering commitment to excellence. Your focus on experimentation and quality aligns seamlessly with my personal values and professional aspirations.", company);
-    let para = Paragraph::new(para3);
-    layout.push(para);
-
-    layout.push(Break::new(1));
-
-    let closing = "Enclosed, please find my resume, which provides a comprehensive overview of my qualifications and accomplishments. I am genuinely eager to explore how my background and experience can contribute to your organization's continued growth. I welcome the opportunity to discuss how we can work together to achieve your strategic goals.";
-    let para = Paragraph::new(closing);
-    layout.push(para);
-
-    layout.push(Break::new(1));
-
-    let para = Paragraph::new("Thank you for considering my application.");
-    layout.push(para);
+    for paragraph in body {
+        let para = Paragraph::new(paragraph.as_str());
+        layout.push(para);
 
-    layout.push(Break::new(1));
+        layout.push(Break::new(1));
+    }
 
     let para = Paragraph::new("Warm regards,");
     layout.push(para);
 
-    layout.push(Break::new(1));
+    match settings.signature.as_ref().and_then(signature_image) {
+        Some(image) => {
+            layout.push(Break::new(0.5));
+            layout.push(image);
+        }
+        None => layout.push(Break::new(1)),
+    }
 
     let para = Paragraph::new(name);
     layout.push(para);
 
-    let margins = Margins::trbl(0, 34.5, 0, 34.5);
-    let ele = PaddedElement::new(layout, margins);
+    let ele = PaddedElement::new(layout, theme.layout.letter);
     doc.push(ele);
 
-    doc.push(Break::new(1));
-
-    let mut para = Paragraph::default();
-    para.set_alignment(Alignment::Center);
-    para.push("CONTACT");
-    doc.push(
-        para.styled(
-            style::Style::new()
-                .bold()
-                .with_color(style::Color::Rgb(129, 173, 187))
-                .with_font_size(8)
-                .with_line_spacing(1.5),
-        ),
-    );
-
-    doc.push(Break::new(0.33));
-
-    let image = Image::from_dynamic_image(
-        Reader::new(Cursor::new(
-            FILES_DIR.get_file("line.jpg").unwrap().contents(),
-        ))
-        .with_guessed_format()
-        .unwrap()
-        .decode()
-        .unwrap(),
-    )
-    .expect("Failed to load test image")
-    .with_alignment(genpdf::Alignment::Center)
-    .with_scale(Scale { x: 2.0, y: 0.5 }); // Center the image on the page.
-
-    doc.push(image);
+    if !contact.is_empty() {
+        doc.push(Break::new(1));
+
+        let mut para = Paragraph::default();
+        para.set_alignment(Alignment::Center);
+        para.push("CONTACT");
+        doc.push(
+            para.styled(
+                style::Style::new()
+                    .bold()
+                    .with_color(theme.colours.accent.into())
+                    .with_font_size(theme.font_sizes.label)
+                    .with_line_spacing(1.5),
+            ),
+        );
+
+        doc.push(Break::new(0.33));
+
+        let image = Image::from_dynamic_image(
+            Reader::new(Cursor::new(
+                FILES_DIR.get_file("line.jpg").unwrap().contents(),
+            ))
+            .with_guessed_format()
+            .unwrap()
+            .decode()
+            .unwrap(),
+        )
+        .expect("Failed to load test image")
+        .with_alignment(genpdf::Alignment::Center)
+        .with_scale(settings.paper.scale(2.0, 0.5)); // Center the image on the page.
+
+        doc.push(image);
+
+        doc.push(Break::new(0.33));
+
+        let mut line = contact_links(contact);
+        line.set_alignment(Alignment::Center);
+        doc.push(
+            line.styled(
+                style::Style::new()
+                    .bold()
+                    .with_color(theme.colours.name.into())
+                    .with_font_size(theme.font_sizes.label)
+                    .with_line_spacing(1.0),
+            ),
+        );
+    }
 
-    doc.push(Break::new(0.33));
+    doc
+}
 
-    let mut para = Paragraph::default();
-    para.set_alignment(Alignment::Center);
-    para.push(r#"+1 (319) 310-2338 | annie.ehler.4@gmail.com | linkedin.com/in/annie444 | github.com/annie444 | annieehler.com"#);
-    doc.push(
-        para.styled(
-            style::Style::new()
-                .bold()
-                .with_color(style::Color::Rgb(26, 92, 113))
-                .with_font_size(8)
-                .with_line_spacing(1.0),
-        ),
-    );
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::metadata::Metadata;
+    use crate::config::ConfigFile;
+    use genpdf::SimplePageDecorator;
+    use image::{ImageFormat, Rgba, RgbaImage};
 
-    let out = tempfile::Builder::new()
-        .suffix(".pdf")
-        .tempfile()
-        .expect("Unable to reach tempfile");
+    fn settings() -> Settings {
+        Settings::load(&ConfigFile::default(), &clap::ArgMatches::default()).unwrap()
+    }
 
-    // Render the document and write it to a file
-    doc.render_to_file(out.path())
-        .expect("Failed to write PDF file");
+    /// How many images the rendered letter draws, its own included.
+    fn images(settings: &Settings) -> usize {
+        let doc = document("Jane Doe", "Acme", None, None, &[], &[], settings);
+        let rendered =
+            pages::render(doc, SimplePageDecorator::new(), &Metadata::default()).unwrap();
+        let pdf = lopdf::Document::load_mem(&rendered.pdf).unwrap();
+        pdf.objects
+            .values()
+            .filter_map(|object| object.as_stream().ok())
+            .filter(|stream| {
+                stream
+                    .dict
+                    .get(b"Subtype")
+                    .and_then(lopdf::Object::as_name_str)
+                    .ok()
+                    == Some("Image")
+            })
+            .count()
+    }
 
-    let cmd = gs_command(out.path(), output)
-        .output()
-        .expect("failed to execute process");
+    #[test]
+    fn unreadable_signatures_are_left_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let garbage = dir.path().join("signature.png");
+        std::fs::write(&garbage, "not an image").unwrap();
+
+        for path in [dir.path().join("missing.png"), garbage] {
+            let signature = signature(&path);
+            assert!(signature.is_err(), "{} was read", path.display());
+            let settings = Settings {
+                signature: signature.ok(),
+                ..settings()
+            };
+            assert_eq!(images(&settings), images(&self::settings()));
+        }
+    }
 
-    io::stdout().write_all(&cmd.stdout).unwrap();
-    io::stderr().write_all(&cmd.stderr).unwrap();
+    #[test]
+    fn flattens_transparency_onto_white() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("signature.png");
+        let mut rgba = RgbaImage::new(3, 1);
+        rgba.put_pixel(0, 0, Rgba([0, 0, 0, 0]));
+        rgba.put_pixel(1, 0, Rgba([200, 0, 0, 255]));
+        rgba.put_pixel(2, 0, Rgba([0, 0, 0, 128]));
+        rgba.save_with_format(&path, ImageFormat::Png).unwrap();
+
+        let image = signature(&path).unwrap();
+        assert!(!image.color().has_alpha());
+        let rgb = image.to_rgb8();
+        assert_eq!(rgb.get_pixel(0, 0), &Rgb([255, 255, 255]));
+        assert_eq!(rgb.get_pixel(1, 0), &Rgb([200, 0, 0]));
+        assert_eq!(rgb.get_pixel(2, 0), &Rgb([127, 127, 127]));
+
+        let signed = Settings {
+            signature: Some(image),
+            ..settings()
+        };
+        assert_eq!(images(&signed), images(&settings()) + 1);
+    }
 
-    out.close().expect("Unable to get rid of tempfile");
+    #[test]
+    fn scales_signatures_into_their_space() {
+        // Wide, tall, and already small enough to grow
+        for (width, height, wide) in [(3000, 300, true), (400, 2000, false), (60, 20, false)] {
+            let scale = signature_scale(width, height);
+            let (w, h) = (mm(width) * scale, mm(height) * scale);
+            assert!(w <= SIGNATURE_WIDTH + 1e-9 && h <= SIGNATURE_HEIGHT + 1e-9);
+            if wide {
+                assert!(
+                    (w - SIGNATURE_WIDTH).abs() < 1e-9,
+                    "{}x{} is {}mm wide",
+                    width,
+                    height,
+                    w
+                );
+            } else {
+                assert!(
+                    (h - SIGNATURE_HEIGHT).abs() < 1e-9,
+                    "{}x{} is {}mm tall",
+                    width,
+                    height,
+                    h
+                );
+            }
+        }
+    }
 }
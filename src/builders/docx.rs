@@ -0,0 +1,496 @@
+use crate::builders::theme::{Colour, Spacing, Theme};
+use crate::builders::utils::{marked_spans, ContactItem, Degree, Project, Skill, WorkExperience};
+use crate::builders::Settings;
+use crate::config::Resume;
+use crate::helpers;
+use std::fmt::Write;
+use std::io::{Cursor, Write as _};
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+const MAIN: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+const RELATIONSHIPS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A colour as Word writes them, e.g. `2B3A55`.
+fn colour(c: Colour) -> String {
+    format!("{:02X}{:02X}{:02X}", c.0, c.1, c.2)
+}
+
+/// Millimetres in twentieths of a point, Word's page unit.
+fn twips(mm: f64) -> i64 {
+    (mm * 1440.0 / 25.4).round() as i64
+}
+
+fn run(text: &str, bold: bool) -> String {
+    format!(
+        "<w:r>{}<w:t xml:space=\"preserve\">{}</w:t></w:r>",
+        if bold { "<w:rPr><w:b/></w:rPr>" } else { "" },
+        escape(text)
+    )
+}
+
+/// Runs for `text`, with its `**emphasised**` spans in bold.
+fn marked(text: &str) -> String {
+    marked_spans(text)
+        .map(|(span, bold)| run(span, bold))
+        .collect()
+}
+
+/// A document's body, and the hyperlinks its relationships need to list.
+#[derive(Default)]
+struct Body {
+    xml: String,
+    links: Vec<String>,
+}
+
+impl Body {
+    fn push(&mut self, style: &str, centred: bool, runs: &str) {
+        write!(self.xml, "<w:p><w:pPr><w:pStyle w:val=\"{}\"/>", style).unwrap();
+        if centred {
+            self.xml.push_str("<w:jc w:val=\"center\"/>");
+        }
+        write!(self.xml, "</w:pPr>{}</w:p>", runs).unwrap();
+    }
+
+    fn paragraph(&mut self, style: &str, runs: &str) {
+        self.push(style, false, runs);
+    }
+
+    fn centred(&mut self, style: &str, runs: &str) {
+        self.push(style, true, runs);
+    }
+
+    fn bullets(&mut self, items: &[String]) {
+        for item in items {
+            self.paragraph("ListBullet", &marked(item));
+        }
+    }
+
+    fn link(&mut self, text: &str, href: &str) -> String {
+        self.links.push(href.to_string());
+        format!(
+            "<w:hyperlink r:id=\"link{}\">{}</w:hyperlink>",
+            self.links.len(),
+            run(text, false)
+        )
+    }
+
+    fn contact(&mut self, items: &[ContactItem]) -> String {
+        let mut runs = String::new();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                runs.push_str(&run(" | ", false));
+            }
+            match item.href() {
+                Some(href) => runs.push_str(&self.link(&item.label, &href)),
+                None => runs.push_str(&run(&item.label, false)),
+            }
+        }
+        runs
+    }
+}
+
+/// The page and text settings a document is packaged with.
+struct Page<'a> {
+    settings: &'a Settings,
+    theme: &'a Theme,
+    margins: Spacing,
+    font_size: u8,
+    line_spacing: f64,
+    /// Space after each paragraph, in points.
+    after: u32,
+}
+
+fn styles(page: &Page) -> String {
+    let theme = page.theme;
+    let colours = &theme.colours;
+    let sizes = &theme.font_sizes;
+    let font = escape(&page.settings.font_name);
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<w:styles xmlns:w=\"{}\">",
+        MAIN
+    );
+    write!(
+        xml,
+        "<w:docDefaults><w:rPrDefault><w:rPr>\
+         <w:rFonts w:ascii=\"{font}\" w:hAnsi=\"{font}\" w:eastAsia=\"{font}\" w:cs=\"{font}\"/>\
+         <w:color w:val=\"{text}\"/><w:sz w:val=\"{size}\"/><w:szCs w:val=\"{size}\"/>\
+         <w:lang w:val=\"en-US\"/></w:rPr></w:rPrDefault>\
+         <w:pPrDefault><w:pPr><w:spacing w:after=\"{after}\" w:line=\"{line}\" w:lineRule=\"auto\"/></w:pPr></w:pPrDefault>\
+         </w:docDefaults>",
+        text = colour(colours.text),
+        size = u32::from(page.font_size) * 2,
+        after = page.after * 20,
+        line = (240.0 * page.line_spacing).round() as i64,
+    )
+    .unwrap();
+    xml.push_str(
+        "<w:style w:type=\"paragraph\" w:default=\"1\" w:styleId=\"Normal\"><w:name w:val=\"Normal\"/><w:qFormat/></w:style>",
+    );
+    let mut style = |id: &str, name: &str, ppr: String, rpr: String| {
+        write!(
+            xml,
+            "<w:style w:type=\"paragraph\" w:styleId=\"{id}\"><w:name w:val=\"{name}\"/>\
+             <w:basedOn w:val=\"Normal\"/><w:next w:val=\"Normal\"/><w:qFormat/>\
+             <w:pPr>{ppr}</w:pPr><w:rPr>{rpr}</w:rPr></w:style>"
+        )
+        .unwrap();
+    };
+    let font = |size: u8, c: Colour, bold: bool| {
+        format!(
+            "{}<w:color w:val=\"{}\"/><w:sz w:val=\"{2}\"/><w:szCs w:val=\"{2}\"/>",
+            if bold { "<w:b/>" } else { "" },
+            colour(c),
+            u32::from(size) * 2
+        )
+    };
+    style(
+        "Title",
+        "Title",
+        "<w:spacing w:after=\"60\"/>".to_string(),
+        font(sizes.name, colours.name, true),
+    );
+    style(
+        "LetterTitle",
+        "Letter Title",
+        "<w:spacing w:after=\"60\"/>".to_string(),
+        font(sizes.letter_name, colours.name, true),
+    );
+    style(
+        "Heading1",
+        "heading 1",
+        format!(
+            "<w:keepNext/><w:pBdr><w:bottom w:val=\"single\" w:sz=\"4\" w:space=\"1\" w:color=\"{}\"/></w:pBdr>\
+             <w:spacing w:before=\"200\" w:after=\"80\"/><w:outlineLvl w:val=\"0\"/>",
+            colour(colours.heading)
+        ),
+        font(sizes.section, colours.heading, true),
+    );
+    style(
+        "Heading2",
+        "heading 2",
+        "<w:keepNext/><w:spacing w:before=\"120\" w:after=\"0\"/><w:outlineLvl w:val=\"1\"/>"
+            .to_string(),
+        font(sizes.entry, colours.heading, true),
+    );
+    style(
+        "Contact",
+        "Contact",
+        "<w:spacing w:after=\"120\"/>".to_string(),
+        font(sizes.contact, colours.muted, false),
+    );
+    style(
+        "Label",
+        "Label",
+        "<w:spacing w:after=\"0\"/>".to_string(),
+        font(sizes.label, colours.accent, true),
+    );
+    style(
+        "ListBullet",
+        "List Bullet",
+        "<w:numPr><w:numId w:val=\"1\"/></w:numPr><w:spacing w:after=\"0\"/><w:contextualSpacing/>"
+            .to_string(),
+        String::new(),
+    );
+    xml.push_str("</w:styles>");
+    xml
+}
+
+fn numbering() -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<w:numbering xmlns:w=\"{}\">\
+         <w:abstractNum w:abstractNumId=\"0\"><w:multiLevelType w:val=\"singleLevel\"/>\
+         <w:lvl w:ilvl=\"0\"><w:start w:val=\"1\"/><w:numFmt w:val=\"bullet\"/><w:lvlText w:val=\"•\"/>\
+         <w:lvlJc w:val=\"left\"/><w:pPr><w:ind w:left=\"360\" w:hanging=\"360\"/></w:pPr></w:lvl>\
+         </w:abstractNum><w:num w:numId=\"1\"><w:abstractNumId w:val=\"0\"/></w:num></w:numbering>",
+        MAIN
+    )
+}
+
+fn document(body: &Body, page: &Page) -> String {
+    let size = page.settings.paper.size();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <w:document xmlns:w=\"{}\" xmlns:r=\"{}\"><w:body>{}\
+         <w:sectPr><w:pgSz w:w=\"{}\" w:h=\"{}\"/>\
+         <w:pgMar w:top=\"{top}\" w:right=\"{}\" w:bottom=\"{top}\" w:left=\"{}\" w:header=\"0\" w:footer=\"0\" w:gutter=\"0\"/>\
+         </w:sectPr></w:body></w:document>",
+        MAIN,
+        RELATIONSHIPS,
+        body.xml,
+        twips(f64::from(size.width)),
+        twips(f64::from(size.height)),
+        twips(page.margins.right),
+        twips(page.margins.left),
+        top = twips(10.0),
+    )
+}
+
+fn relationships(body: &Body) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+    );
+    write!(
+        xml,
+        "<Relationship Id=\"styles\" Type=\"{0}/styles\" Target=\"styles.xml\"/>\
+         <Relationship Id=\"numbering\" Type=\"{0}/numbering\" Target=\"numbering.xml\"/>",
+        RELATIONSHIPS
+    )
+    .unwrap();
+    for (i, href) in body.links.iter().enumerate() {
+        write!(
+            xml,
+            "<Relationship Id=\"link{}\" Type=\"{}/hyperlink\" Target=\"{}\" TargetMode=\"External\"/>",
+            i + 1,
+            RELATIONSHIPS,
+            escape(href)
+        )
+        .unwrap();
+    }
+    xml.push_str("</Relationships>");
+    xml
+}
+
+/// The title, author, subject and keywords Word shows in the file's
+/// properties.
+fn core_properties(settings: &Settings) -> String {
+    let metadata = &settings.metadata;
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <cp:coreProperties xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\" \
+         xmlns:dc=\"http://purl.org/dc/elements/1.1/\" xmlns:dcterms=\"http://purl.org/dc/terms/\" \
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">",
+    );
+    for (name, value) in [
+        ("dc:title", metadata.title.clone()),
+        ("dc:creator", metadata.author.clone()),
+        ("dc:subject", metadata.subject.clone()),
+        ("cp:keywords", metadata.keywords.join(", ")),
+    ] {
+        if !value.is_empty() {
+            write!(xml, "<{0}>{1}</{0}>", name, escape(&value)).unwrap();
+        }
+    }
+    write!(
+        xml,
+        "<dcterms:created xsi:type=\"dcterms:W3CDTF\">{}</dcterms:created></cp:coreProperties>",
+        chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ")
+    )
+    .unwrap();
+    xml
+}
+
+/// Zips the parts of a Word document together.
+fn package(body: &Body, page: &Page) -> Result<Vec<u8>, String> {
+    let parts = [
+        (
+            "[Content_Types].xml",
+            String::from(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+                 <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+                 <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+                 <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+                 <Override PartName=\"/word/document.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml\"/>\
+                 <Override PartName=\"/word/styles.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml\"/>\
+                 <Override PartName=\"/word/numbering.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.numbering+xml\"/>\
+                 <Override PartName=\"/docProps/core.xml\" ContentType=\"application/vnd.openxmlformats-package.core-properties+xml\"/>\
+                 <Override PartName=\"/docProps/app.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.extended-properties+xml\"/>\
+                 </Types>",
+            ),
+        ),
+        (
+            "_rels/.rels",
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+                 <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+                 <Relationship Id=\"document\" Type=\"{0}/officeDocument\" Target=\"word/document.xml\"/>\
+                 <Relationship Id=\"core\" Type=\"http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties\" Target=\"docProps/core.xml\"/>\
+                 <Relationship Id=\"app\" Type=\"{0}/extended-properties\" Target=\"docProps/app.xml\"/>\
+                 </Relationships>",
+                RELATIONSHIPS
+            ),
+        ),
+        ("docProps/core.xml", core_properties(page.settings)),
+        (
+            "docProps/app.xml",
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+                 <Properties xmlns=\"http://schemas.openxmlformats.org/officeDocument/2006/extended-properties\">\
+                 <Application>{} {}</Application></Properties>",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION")
+            ),
+        ),
+        ("word/document.xml", document(body, page)),
+        ("word/styles.xml", styles(page)),
+        ("word/numbering.xml", numbering()),
+        ("word/_rels/document.xml.rels", relationships(body)),
+    ];
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, contents) in parts {
+        zip.start_file(name, options)
+            .and_then(|_| Ok(zip.write_all(contents.as_bytes())?))
+            .map_err(|e| format!("unable to write {}: {}", name, e))?;
+    }
+    let cursor = zip.finish().map_err(|e| e.to_string())?;
+    Ok(cursor.into_inner())
+}
+
+fn skills(body: &mut Body, skills: &[Skill]) {
+    for skill in skills {
+        let runs = run(&skill.section, true) + &run(&skill.skills.join(", "), false);
+        body.paragraph("Normal", &runs);
+    }
+}
+
+fn employment(body: &mut Body, jobs: &[WorkExperience]) {
+    for job in jobs {
+        let title = format!("{}, {}", job.position, job.company);
+        body.paragraph("Heading2", &run(&title, false));
+        let when = format!("{} — {} - {}", job.location, job.start_date, job.end_date);
+        body.paragraph("Normal", &run(&when, false));
+        body.bullets(&job.highlights);
+    }
+}
+
+fn education(body: &mut Body, degrees: &[Degree]) {
+    for degree in degrees {
+        let title = format!("{}, {}", degree.university, degree.degree);
+        body.paragraph("Heading2", &run(&title, false));
+        let when = format!("{}, {}", degree.location, degree.year);
+        body.paragraph("Normal", &run(&when, false));
+        if !degree.description.is_empty() {
+            body.paragraph("Normal", &run(&degree.description, false));
+        }
+    }
+}
+
+fn projects(body: &mut Body, projects: &[Project]) {
+    for project in projects {
+        let title = match &project.nickname {
+            Some(nickname) => format!("{} - {}", nickname, project.title),
+            None => project.title.clone(),
+        };
+        body.paragraph("Heading2", &run(&title, false));
+        let organization = format!("{} - {}", project.organization, project.year);
+        body.paragraph("Normal", &run(&organization, false));
+        if let Some(description) = &project.description {
+            body.paragraph("Normal", &marked(description));
+        }
+        if let Some(url) = &project.url {
+            let link = body.link(url, url);
+            body.paragraph("Contact", &link);
+        }
+    }
+}
+
+/// The resume as a Word document, with the theme's fonts and colours and
+/// the classic layout's order.
+pub fn resume(
+    name: &str,
+    position: &str,
+    resume: &Resume,
+    contact: &[ContactItem],
+    settings: &Settings,
+) -> Result<Vec<u8>, String> {
+    let theme = &settings.layout.theme(&settings.theme);
+    let mut doc = Body::default();
+    doc.paragraph("Title", &run(&helpers::capitalize(name), false));
+    if !contact.is_empty() {
+        let runs = doc.contact(contact);
+        doc.paragraph("Contact", &runs);
+    }
+
+    doc.paragraph("Heading1", &run(&helpers::capitalize(position), false));
+    if let Some(summary) = &resume.summary {
+        doc.paragraph("Normal", &marked(summary));
+    }
+    if !resume.skills.is_empty() {
+        doc.paragraph("Heading1", &run("SKILLS", false));
+        skills(&mut doc, &resume.skills);
+    }
+    if !resume.employment.is_empty() {
+        doc.paragraph("Heading1", &run("EXPERIENCE", false));
+        employment(&mut doc, &resume.employment);
+    }
+    if !resume.education.is_empty() {
+        doc.paragraph("Heading1", &run("EDUCATION", false));
+        education(&mut doc, &resume.education);
+    }
+    if !resume.projects.is_empty() {
+        doc.paragraph("Heading1", &run("PROJECTS", false));
+        projects(&mut doc, &resume.projects);
+    }
+
+    package(
+        &doc,
+        &Page {
+            settings,
+            theme,
+            margins: theme.layout.header,
+            font_size: theme.font_sizes.body,
+            line_spacing: theme.layout.line_spacing,
+            after: 0,
+        },
+    )
+}
+
+/// The cover letter as a Word document, with the PDF's heading, body and
+/// contact block.
+pub fn cover_letter(
+    name: &str,
+    company: &str,
+    location: Option<&str>,
+    hiring_manager: Option<&str>,
+    body: &[String],
+    contact: &[ContactItem],
+    settings: &Settings,
+) -> Result<Vec<u8>, String> {
+    let theme = &settings.theme;
+    let mut doc = Body::default();
+    doc.centred("Label", &run("FROM THE DESK OF", false));
+    doc.centred("LetterTitle", &run(&helpers::capitalize(name), false));
+
+    doc.paragraph("Normal", &run(&helpers::today(), false));
+    let mut address = run(company, false);
+    if let Some(location) = location {
+        address.push_str("<w:r><w:br/></w:r>");
+        address.push_str(&run(location, false));
+    }
+    doc.paragraph("Normal", &address);
+    let greeting = format!("Dear {},", hiring_manager.unwrap_or(company));
+    doc.paragraph("Normal", &run(&greeting, false));
+    for paragraph in body {
+        doc.paragraph("Normal", &run(paragraph, false));
+    }
+    doc.paragraph("Normal", &run("Warm regards,", false));
+    doc.paragraph("Normal", &run(name, false));
+
+    if !contact.is_empty() {
+        doc.centred("Label", &run("CONTACT", false));
+        let runs = doc.contact(contact);
+        doc.centred("Contact", &runs);
+    }
+
+    let letter_body = theme.font_sizes.letter_body;
+    package(
+        &doc,
+        &Page {
+            settings,
+            theme,
+            margins: theme.layout.letter,
+            font_size: letter_body,
+            line_spacing: theme.layout.letter_line_spacing,
+            after: u32::from(letter_body),
+        },
+    )
+}
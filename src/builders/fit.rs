@@ -0,0 +1,260 @@
+use crate::builders::{pages::Rendered, resume, theme::Theme, utils::ContactItem, Settings};
+use crate::config::Resume;
+use crate::keywords;
+
+/// A resume rendered as close to the page limit as the allowed adjustments
+/// could get it.
+pub struct Fitted {
+    pub rendered: Rendered,
+    /// What changed from the configured theme and resume, for the user.
+    pub changes: Vec<String>,
+}
+
+/// The adjustments tried in turn, each on top of the ones before.
+#[derive(Clone, Copy)]
+enum Step {
+    LineSpacing,
+    FontSize,
+    Margins,
+}
+
+static STEPS: [Step; 6] = [
+    Step::LineSpacing,
+    Step::LineSpacing,
+    Step::FontSize,
+    Step::Margins,
+    Step::FontSize,
+    Step::FontSize,
+];
+
+/// Applies `step` to `theme`, returning whether anything could change.
+fn apply(step: Step, theme: &mut Theme) -> bool {
+    let before = *theme;
+    match step {
+        Step::LineSpacing => {
+            let spacing = &mut theme.layout.line_spacing;
+            *spacing = (*spacing - 0.1).max(1.0);
+        }
+        Step::FontSize => {
+            let sizes = &mut theme.font_sizes;
+            for (size, min) in [
+                (&mut sizes.name, 18),
+                (&mut sizes.section, 9),
+                (&mut sizes.entry, 8),
+                (&mut sizes.body, 7),
+                (&mut sizes.contact, 7),
+            ] {
+                if *size > min {
+                    *size -= 1;
+                }
+            }
+        }
+        Step::Margins => {
+            let layout = &mut theme.layout;
+            layout.header.left *= 0.75;
+            layout.header.right *= 0.75;
+            layout.content.right *= 0.75;
+            layout.content.left = (layout.content.left * 0.6).max(layout.header.left);
+        }
+    }
+    *theme != before
+}
+
+/// Drops the highlight least relevant to `query` from the job with the
+/// most, preferring older jobs on a tie and keeping at least one per job.
+/// Equally relevant highlights go from the bottom up.
+fn drop_highlight(resume: &mut Resume, query: &str) -> Option<String> {
+    let job = resume
+        .employment
+        .iter_mut()
+        .filter(|job| job.highlights.len() > 1)
+        .max_by_key(|job| job.highlights.len())?;
+    let documents: Vec<&str> = job.highlights.iter().map(String::as_str).collect();
+    let scores = keywords::rank(&documents, query);
+    let least = scores
+        .iter()
+        .enumerate()
+        .rev()
+        .min_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(i, _)| i)?;
+    let highlight = job.highlights.remove(least);
+    Some(format!("dropped from {}: \"{}\"", job.company, highlight))
+}
+
+fn describe(before: &Theme, after: &Theme) -> Vec<String> {
+    let mut changes: Vec<String> = Vec::new();
+    if before.layout.line_spacing != after.layout.line_spacing {
+        changes.push(format!(
+            "line spacing {:.1} → {:.1}",
+            before.layout.line_spacing, after.layout.line_spacing
+        ));
+    }
+    let sizes: Vec<String> = [
+        ("body", before.font_sizes.body, after.font_sizes.body),
+        (
+            "job titles",
+            before.font_sizes.entry,
+            after.font_sizes.entry,
+        ),
+        (
+            "headings",
+            before.font_sizes.section,
+            after.font_sizes.section,
+        ),
+        ("name", before.font_sizes.name, after.font_sizes.name),
+        (
+            "contact",
+            before.font_sizes.contact,
+            after.font_sizes.contact,
+        ),
+    ]
+    .iter()
+    .filter(|(_, from, to)| from != to)
+    .map(|(what, from, to)| format!("{} {}pt → {}pt", what, from, to))
+    .collect();
+    if !sizes.is_empty() {
+        changes.push(format!("font sizes: {}", sizes.join(", ")));
+    }
+    if before.layout.content != after.layout.content {
+        changes.push(format!(
+            "margins: left {:.0}mm → {:.0}mm, right {:.0}mm → {:.0}mm",
+            before.layout.content.left,
+            after.layout.content.left,
+            before.layout.content.right,
+            after.layout.content.right
+        ));
+    }
+    if before.layout.header != after.layout.header {
+        changes.push(format!(
+            "header margins: left {:.0}mm → {:.0}mm, right {:.0}mm → {:.0}mm",
+            before.layout.header.left,
+            after.layout.header.left,
+            before.layout.header.right,
+            after.layout.header.right
+        ));
+    }
+    changes
+}
+
+/// Renders the resume, tightening the line spacing, font sizes and margins
+/// until it fits in `pages` pages. Highlights are only dropped when `trim` is
+/// set, and only once every other adjustment has been made, least relevant
+/// to `query` first.
+#[allow(clippy::too_many_arguments)]
+pub fn fit(
+    name: &str,
+    position: &str,
+    resume: &Resume,
+    contact: &[ContactItem],
+    settings: &Settings,
+    pages: usize,
+    trim: bool,
+    query: &str,
+) -> Result<Fitted, String> {
+    let original = settings.theme;
+    let mut settings = settings.clone();
+    let mut resume = resume.clone();
+    let mut dropped: Vec<String> = Vec::new();
+    let mut steps = STEPS.iter();
+
+    let mut rendered = resume::render(name, position, &resume, contact, &settings)?;
+    while rendered.pages > pages {
+        let changed = match steps.next() {
+            Some(step) => apply(*step, &mut settings.theme),
+            None if trim => match drop_highlight(&mut resume, query) {
+                Some(change) => {
+                    dropped.push(change);
+                    true
+                }
+                None => break,
+            },
+            None => break,
+        };
+        if changed {
+            rendered = resume::render(name, position, &resume, contact, &settings)?;
+        }
+    }
+
+    let mut changes = describe(&original, &settings.theme);
+    changes.extend(dropped);
+    Ok(Fitted { rendered, changes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::utils::WorkExperience;
+
+    fn job(company: &str, highlights: &[&str]) -> WorkExperience {
+        WorkExperience {
+            position: "Engineer".to_string(),
+            company: company.to_string(),
+            location: "Remote".to_string(),
+            start_date: "2020".to_string(),
+            end_date: "2021".to_string(),
+            highlights: highlights.iter().map(|h| h.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn describes_every_change_it_makes() {
+        let before = Theme::default();
+        let mut after = before;
+        apply(Step::FontSize, &mut after);
+        apply(Step::Margins, &mut after);
+        assert_eq!(
+            describe(&before, &after),
+            vec![
+                "font sizes: body 9pt → 8pt, job titles 10pt → 9pt, headings 12pt → 11pt, \
+                 name 27pt → 26pt, contact 10pt → 9pt",
+                "margins: left 32mm → 19mm, right 12mm → 9mm",
+                "header margins: left 12mm → 9mm, right 12mm → 9mm",
+            ]
+        );
+    }
+
+    #[test]
+    fn drops_the_least_relevant_highlight_of_the_longest_job() {
+        let mut resume = Resume {
+            employment: vec![
+                job("Acme", &["Ran Kubernetes clusters", "Wrote Go services"]),
+                job(
+                    "Initech",
+                    &[
+                        "Organised the summer party",
+                        "Migrated the fleet to Kubernetes",
+                        "Tuned Kubernetes autoscaling",
+                    ],
+                ),
+            ],
+            ..Resume::default()
+        };
+
+        let change = drop_highlight(&mut resume, "Kubernetes engineer");
+        assert_eq!(
+            change.as_deref(),
+            Some("dropped from Initech: \"Organised the summer party\"")
+        );
+        assert_eq!(
+            resume.employment[1].highlights,
+            vec![
+                "Migrated the fleet to Kubernetes",
+                "Tuned Kubernetes autoscaling"
+            ]
+        );
+
+        // Tied at two each, the older job (listed later) goes first. Both
+        // highlights match equally, so the bottom one goes.
+        drop_highlight(&mut resume, "Kubernetes engineer");
+        assert_eq!(
+            resume.employment[1].highlights,
+            vec!["Migrated the fleet to Kubernetes"]
+        );
+        drop_highlight(&mut resume, "Kubernetes engineer");
+        assert_eq!(
+            resume.employment[0].highlights,
+            vec!["Ran Kubernetes clusters"]
+        );
+        assert_eq!(drop_highlight(&mut resume, "Kubernetes engineer"), None);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::builders::FONTS_DIR;
+use crate::config;
+use genpdf::fonts::{FontData, FontFamily};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+pub const DEFAULT_FAMILY: &str = "Rubik";
+
+/// Which fonts the documents are set in. Files are looked up as
+/// `<family>-<style>.ttf`, where the styles for a `regular` weight of `Light`
+/// are `Light` and `LightItalic` (`Regular` pairs with plain `Italic`).
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct FontSetting {
+    /// A directory of TTF files. The bundled fonts are used when it's unset.
+    pub dir: Option<PathBuf>,
+    /// The file name prefix, e.g. `Rubik` for `Rubik-Regular.ttf`.
+    pub family: Option<String>,
+    /// The weight used for body text, e.g. `Light`. Defaults to `Regular`.
+    pub regular: Option<String>,
+    /// The weight used for bold text, e.g. `SemiBold`. Defaults to `Bold`.
+    pub bold: Option<String>,
+}
+
+fn italic(weight: &str) -> String {
+    if weight.eq_ignore_ascii_case("regular") {
+        "Italic".to_string()
+    } else {
+        format!("{}Italic", weight)
+    }
+}
+
+/// Where the font files come from.
+enum Source {
+    Bundled,
+    Dir(PathBuf),
+}
+
+impl Source {
+    fn read(&self, family: &str, style: &str) -> Option<Vec<u8>> {
+        let file = format!("{}-{}.ttf", family, style);
+        match self {
+            Source::Bundled => FONTS_DIR
+                .get_file(format!("{}/{}", family, file))
+                .map(|f| f.contents().to_vec()),
+            Source::Dir(dir) => fs::read(dir.join(file)).ok(),
+        }
+    }
+
+    fn describe(&self, family: &str, style: &str) -> String {
+        match self {
+            Source::Bundled => format!("the bundled {}-{}.ttf", family, style),
+            Source::Dir(dir) => dir
+                .join(format!("{}-{}.ttf", family, style))
+                .display()
+                .to_string(),
+        }
+    }
+
+    /// The families available, for error messages.
+    fn families(&self) -> Vec<String> {
+        match self {
+            Source::Bundled => FONTS_DIR
+                .dirs()
+                .filter_map(|d| d.path().file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .collect(),
+            Source::Dir(_) => Vec::new(),
+        }
+    }
+
+    /// The styles present for `family`, for error messages.
+    fn styles(&self, family: &str) -> Vec<String> {
+        let prefix = format!("{}-", family);
+        let names: Vec<String> = match self {
+            Source::Bundled => FONTS_DIR
+                .get_dir(family)
+                .map(|d| {
+                    d.files()
+                        .filter_map(|f| f.path().file_name())
+                        .map(|n| n.to_string_lossy().to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            Source::Dir(dir) => fs::read_dir(dir)
+                .map(|entries| {
+                    entries
+                        .filter_map(|e| e.ok())
+                        .map(|e| e.file_name().to_string_lossy().to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+        let mut styles: Vec<String> = names
+            .iter()
+            .filter_map(|n| n.strip_prefix(&prefix)?.strip_suffix(".ttf"))
+            .map(str::to_string)
+            .collect();
+        styles.sort();
+        styles
+    }
+}
+
+/// The name of the font family `setting` asks for.
+pub fn family(setting: Option<&FontSetting>) -> String {
+    setting
+        .and_then(|s| s.family.clone())
+        .unwrap_or_else(|| DEFAULT_FAMILY.to_string())
+}
+
+/// Loads the regular, bold, italic and bold italic fonts described by
+/// `setting`, falling back to the bundled Rubik family.
+pub fn load(setting: Option<&FontSetting>) -> Result<FontFamily<FontData>, String> {
+    let family = self::family(setting);
+    let setting = setting.cloned().unwrap_or_default();
+    let source = match setting.dir {
+        Some(dir) => {
+            let dir = config::expand_home(&dir);
+            if !dir.is_dir() {
+                return Err(format!(
+                    "the font directory {} doesn't exist",
+                    dir.display()
+                ));
+            }
+            Source::Dir(dir)
+        }
+        None => Source::Bundled,
+    };
+    let regular = setting.regular.unwrap_or_else(|| "Regular".to_string());
+    let bold = setting.bold.unwrap_or_else(|| "Bold".to_string());
+
+    let available = source.styles(&family);
+    if available.is_empty() {
+        return Err(match source {
+            Source::Bundled => format!(
+                "`{}` isn't a bundled font family (expected one of: {})",
+                family,
+                source.families().join(", ")
+            ),
+            Source::Dir(ref dir) => format!(
+                "{} has no fonts named like `{}-Regular.ttf`",
+                dir.display(),
+                family
+            ),
+        });
+    }
+
+    let face = |style: &str| -> Result<FontData, String> {
+        // Accept `semibold` for `SemiBold` and so on
+        let style = available
+            .iter()
+            .find(|s| s.eq_ignore_ascii_case(style))
+            .map(String::as_str)
+            .unwrap_or(style);
+        let bytes = source.read(&family, style).ok_or_else(|| {
+            format!(
+                "the `{}` font family has no {} style ({} is missing); available styles: {}",
+                family,
+                style,
+                source.describe(&family, style),
+                available.join(", ")
+            )
+        })?;
+        FontData::new(bytes, None)
+            .map_err(|e| format!("unable to read {}: {}", source.describe(&family, style), e))
+    };
+
+    Ok(FontFamily {
+        regular: face(&regular)?,
+        bold: face(&bold)?,
+        italic: face(&italic(&regular))?,
+        bold_italic: face(&italic(&bold))?,
+    })
+}
@@ -0,0 +1,102 @@
+use crate::builders::pages::{Optimization, Saved};
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, str::FromStr};
+
+/// The kinds of file the resume and cover letter can be written as.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Pdf,
+    /// A single page with the styles inline, for websites and web forms.
+    Html,
+    /// Plain text, for pasting into application forms.
+    Txt,
+    /// Markdown, for forms and sites that render it.
+    Md,
+    /// A Word document, for recruiters who ask for one.
+    Docx,
+    /// LaTeX source, for finishing by hand.
+    Tex,
+}
+
+impl Format {
+    pub const NAMES: [&'static str; 6] = ["pdf", "html", "txt", "md", "docx", "tex"];
+
+    /// The file extensions for the format, the usual one first.
+    fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Format::Pdf => &["pdf"],
+            Format::Html => &["html", "htm"],
+            Format::Txt => &["txt"],
+            Format::Md => &["md", "markdown"],
+            Format::Docx => &["docx"],
+            Format::Tex => &["tex"],
+        }
+    }
+
+    /// The format `path`'s extension names, if it's one we write.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        [
+            Format::Pdf,
+            Format::Html,
+            Format::Txt,
+            Format::Md,
+            Format::Docx,
+            Format::Tex,
+        ]
+        .into_iter()
+        .find(|format| format.extensions().contains(&extension.as_str()))
+    }
+
+    /// `out`, with the format's extension added when it has another one.
+    pub fn path(&self, out: PathBuf) -> PathBuf {
+        match out.extension().and_then(|e| e.to_str()) {
+            Some(extension)
+                if !self
+                    .extensions()
+                    .contains(&extension.to_ascii_lowercase().as_str()) =>
+            {
+                PathBuf::from(format!("{}.{}", out.display(), self.extensions()[0]))
+            }
+            _ => out,
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extensions()[0])
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "pdf" => Ok(Format::Pdf),
+            "html" => Ok(Format::Html),
+            "txt" => Ok(Format::Txt),
+            "md" => Ok(Format::Md),
+            "docx" => Ok(Format::Docx),
+            "tex" => Ok(Format::Tex),
+            other => Err(format!(
+                "unknown format `{}` (expected one of: {})",
+                other,
+                Format::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Writes a document that needs no further processing to `out`, adding the
+/// format's extension when it has another one.
+pub fn save(contents: &[u8], out: PathBuf, format: Format) -> Result<Saved, String> {
+    let path = format.path(out);
+    fs::write(&path, contents).map_err(|e| e.to_string())?;
+    Ok(Saved {
+        path,
+        optimization: Optimization::Skipped,
+    })
+}
@@ -0,0 +1,412 @@
+use crate::builders::resume::ResumeLayout;
+use crate::builders::theme::{Spacing, Theme};
+use crate::builders::utils::{marked_spans, ContactItem, Degree, Project, Skill, WorkExperience};
+use crate::builders::Settings;
+use crate::config::Resume;
+use crate::helpers;
+use std::fmt::Write;
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// `text` with any `**emphasised**` spans in bold, as `push_marked` does.
+fn marked(text: &str) -> String {
+    let mut html = String::new();
+    for (span, bold) in marked_spans(text) {
+        if bold {
+            write!(html, "<strong>{}</strong>", escape(span)).unwrap();
+        } else {
+            html.push_str(&escape(span));
+        }
+    }
+    html
+}
+
+fn margins(spacing: &Spacing) -> String {
+    format!(
+        "{:.1}mm {:.1}mm {:.1}mm {:.1}mm",
+        spacing.top, spacing.right, spacing.bottom, spacing.left
+    )
+}
+
+fn link(text: &str, href: Option<String>) -> String {
+    match href {
+        Some(href) => format!("<a href=\"{}\">{}</a>", escape(&href), escape(text)),
+        None => escape(text),
+    }
+}
+
+fn contact_markup(items: &[ContactItem]) -> String {
+    items
+        .iter()
+        .map(|item| link(&item.label, item.href()))
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+/// The `<head>`, with the metadata the PDF would have and `css`.
+fn head(settings: &Settings, css: &str) -> String {
+    let metadata = &settings.metadata;
+    let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+    html.push_str("<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    writeln!(html, "<title>{}</title>", escape(&metadata.title)).unwrap();
+    for (name, content) in [
+        ("author", metadata.author.clone()),
+        ("description", metadata.subject.clone()),
+        ("keywords", metadata.keywords.join(", ")),
+        (
+            "generator",
+            format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+        ),
+    ] {
+        if !content.is_empty() {
+            writeln!(
+                html,
+                "<meta name=\"{}\" content=\"{}\">",
+                name,
+                escape(&content)
+            )
+            .unwrap();
+        }
+    }
+    writeln!(html, "<style>\n{}</style>\n</head>", css).unwrap();
+    html
+}
+
+/// The styles shared by both documents.
+fn base_css(settings: &Settings, theme: &Theme, font_size: u8, line_spacing: f64) -> String {
+    let size = settings.paper.size();
+    format!(
+        "body {{ margin: 0 auto; padding: 10mm 0; max-width: {width:.0}mm; \
+         font-family: \"{font}\", sans-serif; font-size: {font_size}pt; \
+         line-height: {line_spacing}; color: {text}; background: #fff; }}
+h1, h2, p, ul {{ margin: 0; }}
+a {{ color: inherit; text-decoration: none; }}
+a:hover {{ text-decoration: underline; }}
+hr {{ border: 0; border-top: 1px solid {heading}; }}
+@media print {{
+  @page {{ size: {width:.1}mm {height:.1}mm; margin: 0; }}
+  body {{ padding: 0; }}
+}}
+",
+        width = f64::from(size.width),
+        height = f64::from(size.height),
+        font = escape(&settings.font_name),
+        text = theme.colours.text,
+        heading = theme.colours.heading,
+    )
+}
+
+fn resume_css(settings: &Settings, theme: &Theme, gap: f64) -> String {
+    let mut css = base_css(
+        settings,
+        theme,
+        theme.font_sizes.body,
+        theme.layout.line_spacing,
+    );
+    write!(
+        css,
+        "header {{ padding: {header}; }}
+h1 {{ font-size: {name_size}pt; color: {name}; line-height: 1.2; }}
+.contact {{ padding: {header}; font-size: {contact_size}pt; color: {muted}; line-height: 1; }}
+hr {{ margin: 1mm 0; }}
+section {{ padding: {content}; margin-top: {gap:.2}em; }}
+section:first-child {{ margin-top: 0.5em; }}
+h2 {{ font-size: {section_size}pt; color: {heading}; line-height: 1; }}
+.summary, .skills, .description {{ line-height: 1; }}
+.entry {{ font-size: {entry_size}pt; color: {heading}; line-height: 1; }}
+ul {{ padding-left: 1.2em; line-height: 1; }}
+li::marker {{ content: \"• \"; }}
+.projects {{ display: grid; grid-template-columns: repeat({columns}, 1fr); }}
+.project {{ padding: {project}; line-height: 1; }}
+.project .nickname {{ font-style: italic; }}
+.project .url {{ color: {muted}; }}
+.sidebar {{ display: grid; grid-template-columns: 1fr 2fr; }}
+.sidebar aside section {{ padding: 0 4mm 0 {header_left:.1}mm; }}
+.sidebar main section {{ padding: 0 {header_right:.1}mm 0 4mm; }}
+.sidebar main .projects {{ grid-template-columns: repeat({main_columns}, 1fr); }}
+@media (max-width: 40em) {{
+  header, .contact, section, .sidebar aside section, .sidebar main section {{ padding: 0 1em; }}
+  .sidebar, .projects, .sidebar main .projects {{ display: block; }}
+}}
+",
+        header = margins(&theme.layout.header),
+        header_left = theme.layout.header.left,
+        header_right = theme.layout.header.right,
+        content = margins(&theme.layout.content),
+        project = margins(&theme.layout.project_cell),
+        gap = gap * theme.layout.line_spacing,
+        name = theme.colours.name,
+        heading = theme.colours.heading,
+        muted = theme.colours.muted,
+        name_size = theme.font_sizes.name,
+        contact_size = theme.font_sizes.contact,
+        section_size = theme.font_sizes.section,
+        entry_size = theme.font_sizes.entry,
+        columns = theme.layout.project_columns.max(1),
+        main_columns = theme.layout.project_columns.clamp(1, 2),
+    )
+    .unwrap();
+    css
+}
+
+fn section(title: &str, body: &str) -> String {
+    format!(
+        "<section>\n<h2>{}</h2>\n{}</section>\n",
+        escape(&helpers::capitalize(title)),
+        body
+    )
+}
+
+fn skills(skills: &[Skill]) -> String {
+    let mut html = String::new();
+    for skill in skills {
+        writeln!(
+            html,
+            "<p class=\"skills\"><strong>{}</strong>{}</p>",
+            escape(&skill.section),
+            escape(&skill.skills.join(", "))
+        )
+        .unwrap();
+    }
+    html
+}
+
+fn employment(jobs: &[WorkExperience]) -> String {
+    let mut html = String::new();
+    for job in jobs {
+        writeln!(
+            html,
+            "<p class=\"entry\"><strong>{}, {}</strong>; {}  —  {} - {}</p>",
+            escape(&job.position),
+            escape(&job.company),
+            escape(&job.location),
+            escape(&job.start_date),
+            escape(&job.end_date)
+        )
+        .unwrap();
+        if !job.highlights.is_empty() {
+            html.push_str("<ul>\n");
+            for highlight in &job.highlights {
+                writeln!(html, "<li>{}</li>", marked(highlight)).unwrap();
+            }
+            html.push_str("</ul>\n");
+        }
+    }
+    html
+}
+
+fn education(degrees: &[Degree]) -> String {
+    let mut html = String::new();
+    for degree in degrees {
+        writeln!(
+            html,
+            "<p class=\"entry\"><strong>{}, {}</strong>  —  {}, {}</p>",
+            escape(&degree.university),
+            escape(&degree.degree),
+            escape(&degree.location),
+            escape(&degree.year)
+        )
+        .unwrap();
+        writeln!(
+            html,
+            "<p class=\"description\">{}</p>",
+            escape(&degree.description)
+        )
+        .unwrap();
+    }
+    html
+}
+
+fn projects(projects: &[Project]) -> String {
+    let mut html = String::from("<div class=\"projects\">\n");
+    for project in projects {
+        html.push_str("<div class=\"project\">\n<p class=\"entry\">");
+        if let Some(nickname) = &project.nickname {
+            write!(
+                html,
+                "<span class=\"nickname\">{} - </span>",
+                escape(nickname)
+            )
+            .unwrap();
+        }
+        writeln!(html, "{}</p>", escape(&project.title)).unwrap();
+        writeln!(
+            html,
+            "<p>{} - {}</p>",
+            escape(&project.organization),
+            escape(&project.year)
+        )
+        .unwrap();
+        if let Some(description) = &project.description {
+            writeln!(html, "<p class=\"description\">{}</p>", marked(description)).unwrap();
+        }
+        if let Some(url) = &project.url {
+            writeln!(
+                html,
+                "<p class=\"url\">{}</p>",
+                link(url, Some(url.clone()))
+            )
+            .unwrap();
+        }
+        html.push_str("</div>\n");
+    }
+    html.push_str("</div>\n");
+    html
+}
+
+/// The position and summary, as the first section.
+fn introduction(position: &str, resume: &Resume) -> String {
+    let summary = match &resume.summary {
+        Some(summary) => format!("<p class=\"summary\">{}</p>\n", marked(summary)),
+        None => String::new(),
+    };
+    section(position, &summary)
+}
+
+/// The resume as a self-contained HTML page, styled by the theme and laid
+/// out like the PDF.
+pub fn resume(
+    name: &str,
+    position: &str,
+    resume: &Resume,
+    contact: &[ContactItem],
+    settings: &Settings,
+) -> String {
+    let theme = &settings.layout.theme(&settings.theme);
+    let gap = match settings.layout {
+        ResumeLayout::Compact => 0.25,
+        _ => 0.5,
+    };
+    let mut html = head(settings, &resume_css(settings, theme, gap));
+    html.push_str("<body class=\"resume\">\n<header>\n");
+    writeln!(html, "<h1>{}</h1>", escape(&helpers::capitalize(name))).unwrap();
+    html.push_str("</header>\n<hr>\n");
+    if !contact.is_empty() {
+        writeln!(html, "<p class=\"contact\">{}</p>", contact_markup(contact)).unwrap();
+    }
+
+    let mut side = String::new();
+    let mut main = introduction(position, resume);
+    let sidebar = settings.layout == ResumeLayout::Sidebar;
+    if !resume.skills.is_empty() {
+        let skills = section("SKILLS", &skills(&resume.skills));
+        if sidebar {
+            side.push_str(&skills);
+        } else {
+            main.push_str(&skills);
+        }
+    }
+    if !resume.employment.is_empty() {
+        main.push_str(&section("EXPERIENCE", &employment(&resume.employment)));
+    }
+    if !resume.education.is_empty() {
+        let education = section("EDUCATION", &education(&resume.education));
+        if sidebar {
+            side.push_str(&education);
+        } else {
+            main.push_str(&education);
+        }
+    }
+    if !resume.projects.is_empty() {
+        main.push_str(&section("PROJECTS", &projects(&resume.projects)));
+    }
+
+    if sidebar {
+        write!(
+            html,
+            "<div class=\"sidebar\">\n<aside>\n{}</aside>\n<main>\n{}</main>\n</div>\n",
+            side, main
+        )
+        .unwrap();
+    } else {
+        write!(html, "<main>\n{}</main>\n", main).unwrap();
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn letter_css(settings: &Settings, theme: &Theme) -> String {
+    let mut css = base_css(
+        settings,
+        theme,
+        theme.font_sizes.letter_body,
+        theme.layout.letter_line_spacing,
+    );
+    write!(
+        css,
+        "header {{ padding: {letter_header}; text-align: center; }}
+.label {{ font-size: {label_size}pt; font-weight: bold; color: {accent}; line-height: 1.5; text-align: center; }}
+h1 {{ font-size: {name_size}pt; color: {name}; line-height: 1.2; }}
+hr {{ width: 60%; margin: 0.5em auto 1em; }}
+main {{ padding: {letter}; }}
+main p + p {{ margin-top: 1em; }}
+main .address {{ margin-top: 1em; }}
+main .address + .address {{ margin-top: 0; }}
+footer {{ margin-top: 1em; }}
+footer hr {{ width: 30%; margin: 0.33em auto; }}
+.contact {{ font-size: {label_size}pt; font-weight: bold; color: {name}; line-height: 1; text-align: center; }}
+@media (max-width: 40em) {{
+  header, main {{ padding: 0 1em; }}
+}}
+",
+        letter_header = margins(&theme.layout.letter_header),
+        letter = margins(&theme.layout.letter),
+        label_size = theme.font_sizes.label,
+        name_size = theme.font_sizes.letter_name,
+        accent = theme.colours.accent,
+        name = theme.colours.name,
+    )
+    .unwrap();
+    css
+}
+
+/// The cover letter as a self-contained HTML page, styled by the theme and
+/// laid out like the PDF.
+pub fn cover_letter(
+    name: &str,
+    company: &str,
+    location: Option<&str>,
+    hiring_manager: Option<&str>,
+    body: &[String],
+    contact: &[ContactItem],
+    settings: &Settings,
+) -> String {
+    let theme = &settings.theme;
+    let mut html = head(settings, &letter_css(settings, theme));
+    html.push_str("<body class=\"cover-letter\">\n<header>\n");
+    html.push_str("<p class=\"label\">FROM THE DESK OF</p>\n");
+    writeln!(html, "<h1>{}</h1>", escape(&helpers::capitalize(name))).unwrap();
+    html.push_str("</header>\n<hr>\n<main>\n");
+
+    writeln!(html, "<p>{}</p>", escape(&helpers::today())).unwrap();
+    writeln!(html, "<p class=\"address\">{}</p>", escape(company)).unwrap();
+    if let Some(location) = location {
+        writeln!(html, "<p class=\"address\">{}</p>", escape(location)).unwrap();
+    }
+    writeln!(
+        html,
+        "<p>Dear {},</p>",
+        escape(hiring_manager.unwrap_or(company))
+    )
+    .unwrap();
+    for paragraph in body {
+        writeln!(html, "<p>{}</p>", escape(paragraph)).unwrap();
+    }
+    html.push_str("<p>Warm regards,</p>\n");
+    writeln!(html, "<p>{}</p>", escape(name)).unwrap();
+    html.push_str("</main>\n");
+
+    if !contact.is_empty() {
+        html.push_str("<footer>\n<p class=\"label\">CONTACT</p>\n<hr>\n");
+        writeln!(html, "<p class=\"contact\">{}</p>", contact_markup(contact)).unwrap();
+        html.push_str("</footer>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
@@ -0,0 +1,316 @@
+use genpdf::{
+    error::Error,
+    render::Area,
+    style::{Color, Style},
+    Alignment, Context, Element, Mm, Position, RenderResult, Size,
+};
+use lopdf::{content::Content, dictionary, Object, ObjectId};
+use std::cell::RefCell;
+
+// genpdf can't add annotations and doesn't say where on the page an element
+// ends up, so each link is drawn as an invisible line from the top left to
+// the bottom right of its text. The line's colour holds the link's index,
+// and `annotate` swaps the lines for link annotations once the PDF is done.
+// The colour goes through printpdf, which writes two decimal places, so the
+// index is spread over green and blue in steps of a fiftieth and red marks
+// the line.
+
+/// The red of a link's line; no other lines are stroked in this colour.
+const MARKER_RED: u8 = 253;
+/// The values of green and blue used for link indexes.
+const MARKER_DIGITS: usize = 50;
+
+thread_local! {
+    /// The targets of the links drawn so far, while a document is rendered.
+    static TARGETS: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+fn marker(index: usize) -> Color {
+    let digit = |d: usize| ((d * 255 + MARKER_DIGITS / 2) / MARKER_DIGITS) as u8;
+    Color::Rgb(
+        MARKER_RED,
+        digit(index / MARKER_DIGITS),
+        digit(index % MARKER_DIGITS),
+    )
+}
+
+/// Reads a link's index back from its line's colour, as written to the PDF.
+fn marker_index(rgb: &[f64]) -> Option<usize> {
+    match *rgb {
+        [r, g, b] if (r - f64::from(MARKER_RED) / 255.0).abs() < 0.006 => {
+            let digit = |c: f64| (c * MARKER_DIGITS as f64).round() as usize;
+            Some(digit(g) * MARKER_DIGITS + digit(b))
+        }
+        _ => None,
+    }
+}
+
+/// Records a link's target, returning its index, when links are being
+/// collected.
+fn register(target: &str) -> Option<usize> {
+    TARGETS.with(|targets| {
+        let mut targets = targets.borrow_mut();
+        let targets = targets.as_mut()?;
+        if targets.len() >= MARKER_DIGITS * MARKER_DIGITS {
+            return None;
+        }
+        targets.push(target.to_string());
+        Some(targets.len() - 1)
+    })
+}
+
+/// Runs `render`, collecting the targets of the links drawn meanwhile.
+pub fn collect<T>(render: impl FnOnce() -> T) -> (T, Vec<String>) {
+    TARGETS.with(|targets| *targets.borrow_mut() = Some(Vec::new()));
+    let result = render();
+    let targets = TARGETS.with(|targets| targets.borrow_mut().take());
+    (result, targets.unwrap_or_default())
+}
+
+struct Piece {
+    text: String,
+    style: Style,
+    link: Option<String>,
+    /// Separators are left out at the start and end of a line.
+    separator: bool,
+}
+
+/// A line of text, wrapped between pieces, where any piece can link to a URL.
+pub struct LinkLine {
+    pieces: Vec<Piece>,
+    alignment: Alignment,
+}
+
+impl Default for LinkLine {
+    fn default() -> Self {
+        Self {
+            pieces: Vec::new(),
+            alignment: Alignment::Left,
+        }
+    }
+}
+
+impl LinkLine {
+    pub fn set_alignment(&mut self, alignment: Alignment) {
+        self.alignment = alignment;
+    }
+
+    pub fn push(&mut self, text: impl Into<String>, link: Option<String>) {
+        self.push_styled(text, Style::new(), link);
+    }
+
+    pub fn push_styled(&mut self, text: impl Into<String>, style: Style, link: Option<String>) {
+        self.pieces.push(Piece {
+            text: text.into(),
+            style,
+            link,
+            separator: false,
+        });
+    }
+
+    pub fn push_separator(&mut self, text: impl Into<String>) {
+        self.pieces.push(Piece {
+            text: text.into(),
+            style: Style::new(),
+            link: None,
+            separator: true,
+        });
+    }
+
+    pub fn append(&mut self, other: LinkLine) {
+        self.pieces.extend(other.pieces);
+    }
+
+    /// Splits the pieces into lines no wider than `width`, as indexes.
+    fn wrap(&self, widths: &[Mm], width: Mm) -> Vec<Vec<usize>> {
+        let mut lines: Vec<Vec<usize>> = Vec::new();
+        let mut line: Vec<usize> = Vec::new();
+        let mut used = Mm::from(0);
+        for (i, piece) in self.pieces.iter().enumerate() {
+            if piece.separator {
+                if !line.is_empty() {
+                    line.push(i);
+                    used += widths[i];
+                }
+                continue;
+            }
+            if used + widths[i] > width && line.iter().any(|&j| !self.pieces[j].separator) {
+                lines.push(self.trimmed(line));
+                line = Vec::new();
+                used = Mm::from(0);
+            }
+            line.push(i);
+            used += widths[i];
+        }
+        if !line.is_empty() {
+            lines.push(self.trimmed(line));
+        }
+        lines
+    }
+
+    fn trimmed(&self, mut line: Vec<usize>) -> Vec<usize> {
+        while line.last().is_some_and(|&i| self.pieces[i].separator) {
+            line.pop();
+        }
+        line
+    }
+}
+
+impl Element for LinkLine {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, Error> {
+        let mut result = RenderResult::default();
+        let styles: Vec<Style> = self.pieces.iter().map(|p| style.and(p.style)).collect();
+        let widths: Vec<Mm> = self
+            .pieces
+            .iter()
+            .zip(&styles)
+            .map(|(piece, style)| style.str_width(&context.font_cache, &piece.text))
+            .collect();
+        let width = area.size().width;
+        let lines = self.wrap(&widths, width);
+        let line_height = styles
+            .iter()
+            .map(|style| style.line_height(&context.font_cache))
+            .fold(style.line_height(&context.font_cache), Mm::max);
+        let height = line_height * lines.len() as f64;
+        if height > area.size().height {
+            result.has_more = true;
+            return Ok(result);
+        }
+
+        for (n, line) in lines.iter().enumerate() {
+            let used: Mm = line.iter().map(|&i| widths[i]).sum();
+            let mut x = match self.alignment {
+                Alignment::Left => Mm::from(0),
+                Alignment::Center => (width - used) / 2.0,
+                Alignment::Right => width - used,
+            };
+            let y = line_height * n as f64;
+            for &i in line {
+                let piece = &self.pieces[i];
+                area.print_str(
+                    &context.font_cache,
+                    Position::new(x, y),
+                    styles[i],
+                    &piece.text,
+                )?;
+                if let Some(index) = piece.link.as_deref().and_then(register) {
+                    area.draw_line(
+                        vec![
+                            Position::new(x, y),
+                            Position::new(x + widths[i], y + line_height),
+                        ],
+                        Style::new().with_color(marker(index)),
+                    );
+                }
+                x += widths[i];
+            }
+        }
+
+        result.size = Size::new(width, height);
+        Ok(result)
+    }
+}
+
+/// Replaces the lines drawn for links in `doc` with link annotations to
+/// `targets`, as collected while it was rendered.
+pub fn annotate(doc: &mut lopdf::Document, targets: &[String]) -> Result<(), String> {
+    if targets.is_empty() {
+        return Ok(());
+    }
+    let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    for page in pages {
+        let content = doc.get_page_content(page).map_err(|e| e.to_string())?;
+        let content = Content::decode(&content).map_err(|e| e.to_string())?;
+        let (operations, links) = extract(content.operations);
+        if links.is_empty() {
+            continue;
+        }
+        let content = Content { operations }.encode().map_err(|e| e.to_string())?;
+        doc.change_page_content(page, content)
+            .map_err(|e| e.to_string())?;
+
+        let mut annotations: Vec<Object> = Vec::new();
+        for (index, rect) in links {
+            let Some(target) = targets.get(index) else {
+                continue;
+            };
+            let annotation = doc.add_object(dictionary! {
+                "Type" => "Annot",
+                "Subtype" => "Link",
+                "Rect" => rect.iter().map(|&v| Object::Real(v)).collect::<Vec<Object>>(),
+                "Border" => vec![0.into(), 0.into(), 0.into()],
+                "A" => dictionary! {
+                    "Type" => "Action",
+                    "S" => "URI",
+                    "URI" => Object::string_literal(target.as_str()),
+                },
+            });
+            annotations.push(annotation.into());
+        }
+        let page = doc
+            .get_object_mut(page)
+            .and_then(Object::as_dict_mut)
+            .map_err(|e| e.to_string())?;
+        match page.get_mut(b"Annots").and_then(Object::as_array_mut) {
+            Ok(existing) => existing.extend(annotations),
+            Err(_) => page.set("Annots", annotations),
+        }
+    }
+    Ok(())
+}
+
+type Operations = Vec<lopdf::content::Operation>;
+
+/// Takes the links' lines out of a page's content, returning what's left and
+/// each link's index and rectangle.
+fn extract(operations: Operations) -> (Operations, Vec<(usize, [f64; 4])>) {
+    let mut kept: Operations = Vec::with_capacity(operations.len());
+    let mut links = Vec::new();
+    let mut i = 0;
+    while i < operations.len() {
+        if let Some((index, rect)) = link_at(&operations[i..]) {
+            links.push((index, rect));
+            // The colour, the two points and the stroke, then genpdf's reset
+            // of the colour to black.
+            i += 4;
+            if operations.get(i).is_some_and(|op| op.operator == "RG") {
+                i += 1;
+            }
+            continue;
+        }
+        kept.push(operations[i].clone());
+        i += 1;
+    }
+    (kept, links)
+}
+
+fn link_at(operations: &[lopdf::content::Operation]) -> Option<(usize, [f64; 4])> {
+    let [colour, from, to, stroke, ..] = operations else {
+        return None;
+    };
+    let numbers = |op: &lopdf::content::Operation| -> Option<Vec<f64>> {
+        op.operands
+            .iter()
+            .map(|o| o.as_f64().or_else(|_| o.as_i64().map(|i| i as f64)).ok())
+            .collect()
+    };
+    if colour.operator != "RG"
+        || from.operator != "m"
+        || to.operator != "l"
+        || stroke.operator != "S"
+    {
+        return None;
+    }
+    let index = marker_index(&numbers(colour)?)?;
+    let (from, to) = (numbers(from)?, numbers(to)?);
+    let ([x1, y1], [x2, y2]) = (from.as_slice(), to.as_slice()) else {
+        return None;
+    };
+    Some((index, [x1.min(*x2), y1.min(*y2), x1.max(*x2), y1.max(*y2)]))
+}
@@ -0,0 +1,193 @@
+use crate::config::Resume;
+use lopdf::{Object, StringFormat};
+
+/// What document managers and applicant tracking systems show for a PDF.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metadata {
+    pub title: String,
+    pub author: String,
+    pub subject: String,
+    pub keywords: Vec<String>,
+}
+
+/// The application the PDF is for, as its subject.
+fn subject(position: Option<&str>, company: Option<&str>) -> String {
+    match (position, company) {
+        (Some(position), Some(company)) => format!("{} at {}", position, company),
+        (Some(position), None) => position.to_string(),
+        (None, Some(company)) => format!("Application to {}", company),
+        (None, None) => String::new(),
+    }
+}
+
+/// Every skill on the resume, once each.
+fn keywords(resume: Option<&Resume>) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    for skill in resume
+        .iter()
+        .flat_map(|r| &r.skills)
+        .flat_map(|s| &s.skills)
+    {
+        let skill = skill.trim();
+        if !skill.is_empty() && !keywords.iter().any(|k| k.eq_ignore_ascii_case(skill)) {
+            keywords.push(skill.to_string());
+        }
+    }
+    keywords
+}
+
+impl Metadata {
+    pub fn resume(name: &str, position: &str, company: Option<&str>, resume: &Resume) -> Self {
+        Self {
+            title: format!("{} - Resume", name),
+            author: name.to_string(),
+            subject: subject(Some(position), company),
+            keywords: keywords(Some(resume)),
+        }
+    }
+
+    pub fn cover_letter(
+        name: &str,
+        company: &str,
+        position: Option<&str>,
+        resume: Option<&Resume>,
+    ) -> Self {
+        Self {
+            title: format!("{} - Cover Letter for {}", name, company),
+            author: name.to_string(),
+            subject: subject(position, Some(company)),
+            keywords: keywords(resume),
+        }
+    }
+
+    /// Shared by the cover letter and resume in an application packet.
+    pub fn packet(name: &str, position: &str, company: &str, resume: &Resume) -> Self {
+        Self {
+            title: format!("{} - Application to {}", name, company),
+            author: name.to_string(),
+            subject: subject(Some(position), Some(company)),
+            keywords: keywords(Some(resume)),
+        }
+    }
+
+    /// Replaces the generated title, when one is given.
+    pub fn with_title(mut self, title: Option<&str>) -> Self {
+        if let Some(title) = title {
+            self.title = title.to_string();
+        }
+        self
+    }
+
+    /// Writes the metadata to both the document information dictionary and
+    /// the XMP packet, which some readers prefer.
+    pub fn apply(&self, doc: &mut lopdf::Document) -> Result<(), String> {
+        let creator = format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+        let keywords = self.keywords.join(", ");
+        let fields = [
+            ("Title", self.title.as_str()),
+            ("Author", self.author.as_str()),
+            ("Subject", self.subject.as_str()),
+            ("Keywords", keywords.as_str()),
+            ("Creator", creator.as_str()),
+        ];
+
+        let info = match doc.trailer.get(b"Info").and_then(Object::as_reference) {
+            Ok(info) => info,
+            Err(_) => {
+                let info = doc.add_object(lopdf::Dictionary::new());
+                doc.trailer.set("Info", info);
+                info
+            }
+        };
+        let info = doc
+            .get_object_mut(info)
+            .and_then(Object::as_dict_mut)
+            .map_err(|e| e.to_string())?;
+        for (key, value) in fields.iter().filter(|(_, value)| !value.is_empty()) {
+            info.set(*key, text(value));
+        }
+
+        let xmp = doc
+            .catalog()
+            .and_then(|catalog| catalog.get(b"Metadata"))
+            .and_then(Object::as_reference);
+        if let Ok(xmp) = xmp {
+            let stream = doc
+                .get_object_mut(xmp)
+                .and_then(Object::as_stream_mut)
+                .map_err(|e| e.to_string())?;
+            let packet = String::from_utf8_lossy(&stream.content).into_owned();
+            stream.set_plain_content(self.xmp(&packet, &creator).into_bytes());
+            // Left readable for tools that scan files for the packet
+            stream.allows_compression = false;
+        }
+        Ok(())
+    }
+
+    /// `packet` with the title replaced and the other fields added.
+    fn xmp(&self, packet: &str, creator: &str) -> String {
+        let mut fields = String::new();
+        let mut field = |name: &str, value: String| {
+            fields.push_str(&format!("         <{0}>{1}</{0}>\n", name, value));
+        };
+        let alt = |value: &str| {
+            format!(
+                "\n            <rdf:Alt>\n               <rdf:li xml:lang=\"x-default\">{}</rdf:li>\n            </rdf:Alt>\n         ",
+                escape(value)
+            )
+        };
+        if !self.title.is_empty() {
+            field("dc:title", alt(&self.title));
+        }
+        if !self.author.is_empty() {
+            field(
+                "dc:creator",
+                format!(
+                    "\n            <rdf:Seq>\n               <rdf:li>{}</rdf:li>\n            </rdf:Seq>\n         ",
+                    escape(&self.author)
+                ),
+            );
+        }
+        if !self.subject.is_empty() {
+            field("dc:description", alt(&self.subject));
+        }
+        if !self.keywords.is_empty() {
+            field("pdf:Keywords", escape(&self.keywords.join(", ")));
+        }
+        field("xmp:CreatorTool", escape(creator));
+
+        let mut packet = packet.to_string();
+        if let (Some(start), Some(end)) = (packet.find("<dc:title>"), packet.find("</dc:title>")) {
+            let mut end = end + "</dc:title>".len();
+            if packet[end..].starts_with('\n') {
+                end += 1;
+            }
+            let start = packet[..start].rfind('\n').map_or(start, |i| i + 1);
+            packet.replace_range(start..end, "");
+        }
+        if let Some(at) = packet.find("      </rdf:Description>") {
+            packet.insert_str(at, &fields);
+        }
+        packet
+    }
+}
+
+/// A PDF text string: plain when it's ASCII, UTF-16 otherwise.
+fn text(s: &str) -> Object {
+    if s.is_ascii() {
+        Object::string_literal(s)
+    } else {
+        let mut bytes = vec![0xfe, 0xff];
+        for unit in s.encode_utf16() {
+            bytes.extend_from_slice(&unit.to_be_bytes());
+        }
+        Object::String(bytes, StringFormat::Hexadecimal)
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,7 +1,100 @@
 pub mod coverletter;
+pub mod docx;
+pub mod fit;
+pub mod font;
+pub mod format;
+pub mod html;
+pub mod links;
+pub mod metadata;
+pub mod packet;
+pub mod pages;
+pub mod paper;
 pub mod resume;
+pub mod template;
+pub mod tex;
+pub mod text;
+pub mod theme;
 pub mod utils;
 
+use crate::config::ConfigFile;
+use clap::ArgMatches;
+use format::Format;
+use genpdf::fonts::{FontData, FontFamily};
 use include_dir::{include_dir, Dir};
+use paper::Paper;
+use resume::ResumeLayout;
+use std::path::PathBuf;
+use theme::Theme;
+
 pub static FONTS_DIR: Dir<'_> = include_dir!("./fonts");
 pub static FILES_DIR: Dir<'_> = include_dir!("./static");
+
+/// How a document looks and is written, from the command line and
+/// `~/.cv.config.yaml`.
+#[derive(Clone)]
+pub struct Settings {
+    /// Already fitted to the paper width.
+    pub theme: Theme,
+    pub font_family: FontFamily<FontData>,
+    /// The family's name, for formats that name the font instead of embedding it.
+    pub font_name: String,
+    pub paper: Paper,
+    /// Only used by the resume.
+    pub layout: ResumeLayout,
+    pub pages: pages::Pages,
+    /// Run the PDF through Ghostscript when it's installed.
+    pub optimize: bool,
+    pub format: Format,
+    /// Filled in by the command for each application.
+    pub metadata: metadata::Metadata,
+    /// Drawn above the typed name on PDF cover letters. Loaded by the command,
+    /// which warns and goes without when the file can't be read.
+    pub signature: Option<image::DynamicImage>,
+}
+
+impl Settings {
+    /// Reads `--theme`, `--paper`, `--layout`, `--optimize` and `--format` when the command
+    /// has them, falling back to the config and then the defaults. The format also
+    /// follows the output file's extension.
+    pub fn load(conf: &ConfigFile, matches: &ArgMatches) -> Result<Self, String> {
+        let theme_arg = matches.try_get_one::<String>("theme").ok().flatten();
+        let theme = theme::select(theme_arg.map(String::as_str), conf.theme.as_ref())
+            .map_err(|e| format!("Unable to load the theme: {}", e))?;
+        let font_family = font::load(conf.font.as_ref())
+            .map_err(|e| format!("Unable to load the fonts: {}", e))?;
+        let paper = matches
+            .try_get_one::<Paper>("paper")
+            .ok()
+            .flatten()
+            .copied()
+            .or(conf.paper)
+            .unwrap_or_else(Paper::from_locale);
+        let layout = match matches.try_get_one::<String>("layout").ok().flatten() {
+            Some(layout) => layout.parse()?,
+            None => conf.layout.unwrap_or_default(),
+        };
+        let optimize = matches.try_get_one::<bool>("optimize").ok().flatten() == Some(&true)
+            || conf.optimize.unwrap_or(false);
+        let format = match matches.try_get_one::<String>("format").ok().flatten() {
+            Some(format) => format.parse()?,
+            None => matches
+                .try_get_one::<PathBuf>("output")
+                .ok()
+                .flatten()
+                .and_then(|out| Format::from_path(out))
+                .unwrap_or_default(),
+        };
+        Ok(Settings {
+            theme: theme.fit_width(paper.ratio()),
+            font_family,
+            font_name: font::family(conf.font.as_ref()),
+            paper,
+            layout,
+            pages: conf.pages.clone().unwrap_or_default(),
+            optimize,
+            format,
+            metadata: metadata::Metadata::default(),
+            signature: None,
+        })
+    }
+}
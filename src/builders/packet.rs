@@ -0,0 +1,179 @@
+use crate::builders::metadata::Metadata;
+use crate::builders::pages::{self, Decorator, Rendered, Saved};
+use crate::builders::utils::ContactItem;
+use crate::builders::{coverletter, resume, Settings};
+use crate::config::Resume;
+use lopdf::{dictionary, Document, Object, ObjectId};
+use std::path::PathBuf;
+
+/// Joins PDFs into one, in order, with a bookmark to the first page of each
+/// and `metadata` for the whole.
+pub fn merge(parts: &[(&str, &[u8])], metadata: &Metadata) -> Result<Vec<u8>, String> {
+    let mut merged = Document::with_version("1.5");
+    let mut kids: Vec<ObjectId> = Vec::new();
+    let mut starts: Vec<ObjectId> = Vec::new();
+    let mut xmp = None;
+    for (title, pdf) in parts {
+        let mut doc = Document::load_mem(pdf).map_err(|e| e.to_string())?;
+        doc.renumber_objects_with(merged.max_id + 1);
+        merged.max_id = doc.max_id;
+        let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        starts.push(*pages.first().ok_or(format!("the {} has no pages", title))?);
+        kids.extend(pages);
+        if xmp.is_none() {
+            xmp = doc
+                .catalog()
+                .and_then(|catalog| catalog.get(b"Metadata"))
+                .and_then(Object::as_reference)
+                .ok();
+        }
+        merged.objects.extend(doc.objects);
+    }
+
+    let pages_id = merged.new_object_id();
+    for kid in &kids {
+        merged
+            .get_object_mut(*kid)
+            .and_then(Object::as_dict_mut)
+            .map_err(|e| e.to_string())?
+            .set("Parent", pages_id);
+    }
+    merged.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids.iter().map(|&id| Object::Reference(id)).collect::<Vec<Object>>(),
+            "Count" => kids.len() as i64,
+        }),
+    );
+
+    let outlines_id = merged.new_object_id();
+    let items: Vec<ObjectId> = parts.iter().map(|_| merged.new_object_id()).collect();
+    for (i, ((title, _), start)) in parts.iter().zip(&starts).enumerate() {
+        let mut item = dictionary! {
+            "Title" => Object::string_literal(*title),
+            "Parent" => outlines_id,
+            "Dest" => vec![Object::Reference(*start), "Fit".into()],
+        };
+        if i > 0 {
+            item.set("Prev", items[i - 1]);
+        }
+        if let Some(next) = items.get(i + 1) {
+            item.set("Next", *next);
+        }
+        merged.objects.insert(items[i], Object::Dictionary(item));
+    }
+    if let (Some(first), Some(last)) = (items.first(), items.last()) {
+        merged.objects.insert(
+            outlines_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Outlines",
+                "First" => *first,
+                "Last" => *last,
+                "Count" => items.len() as i64,
+            }),
+        );
+    }
+
+    let mut catalog = dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+        "Outlines" => outlines_id,
+        "PageMode" => "UseOutlines",
+    };
+    // The first part's XMP packet is rewritten for the whole below
+    if let Some(xmp) = xmp {
+        catalog.set("Metadata", xmp);
+    }
+    let catalog_id = merged.add_object(catalog);
+    merged.trailer.set("Root", catalog_id);
+    // Drops the parts' own catalogs, page trees and information dictionaries
+    merged.prune_objects();
+
+    metadata.apply(&mut merged)?;
+    merged.compress();
+    let mut pdf: Vec<u8> = Vec::new();
+    merged.save_to(&mut pdf).map_err(|e| e.to_string())?;
+    Ok(pdf)
+}
+
+/// Renders the cover letter and resume as one PDF. Both share the packet's
+/// header and metadata, and the pages are numbered straight through.
+#[allow(clippy::too_many_arguments)]
+pub fn render(
+    name: &str,
+    position: &str,
+    company: &str,
+    location: Option<&str>,
+    hiring_manager: Option<&str>,
+    body: &[String],
+    resume: &Resume,
+    contact: &[ContactItem],
+    settings: &Settings,
+) -> Result<Rendered, String> {
+    let theme = &settings.theme;
+    let resume_theme = settings.layout.theme(theme);
+    let options = &settings.pages.packet;
+    let decorator = |before: usize, total: Option<usize>| {
+        Decorator::new(options, name, contact, theme, theme.layout.header).in_packet(before, total)
+    };
+    let letter = || {
+        coverletter::document(
+            name,
+            company,
+            location,
+            hiring_manager,
+            body,
+            contact,
+            settings,
+        )
+    };
+    let cv = || resume::document(name, position, resume, contact, settings, &resume_theme);
+
+    let metadata = &settings.metadata;
+    let mut letter_pdf = pages::render(letter(), decorator(0, None), metadata)?;
+    let mut resume_pdf = pages::render(cv(), decorator(0, None), metadata)?;
+    let total = letter_pdf.pages + resume_pdf.pages;
+    if options.page_numbers {
+        // The footers were left blank without the page count. The space they
+        // take was kept, so the pages break in the same places again.
+        letter_pdf = pages::render(letter(), decorator(0, Some(total)), metadata)?;
+        resume_pdf = pages::render(cv(), decorator(letter_pdf.pages, Some(total)), metadata)?;
+    }
+
+    let pdf = merge(
+        &[
+            ("Cover Letter", &letter_pdf.pdf),
+            ("Resume", &resume_pdf.pdf),
+        ],
+        metadata,
+    )?;
+    Ok(Rendered { pdf, pages: total })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn build(
+    name: String,
+    position: String,
+    company: String,
+    location: Option<String>,
+    hiring_manager: Option<String>,
+    body: Vec<String>,
+    resume: Resume,
+    contact: Vec<ContactItem>,
+    settings: Settings,
+    out: PathBuf,
+) -> Result<Saved, String> {
+    let rendered = render(
+        &name,
+        &position,
+        &company,
+        location.as_deref(),
+        hiring_manager.as_deref(),
+        &body,
+        &resume,
+        &contact,
+        &settings,
+    )?;
+    pages::save(&rendered.pdf, out, settings.optimize)
+}
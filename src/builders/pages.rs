@@ -0,0 +1,307 @@
+use crate::builders::format::Format;
+use crate::builders::links::{self, LinkLine};
+use crate::builders::metadata::Metadata;
+use crate::builders::theme::{Spacing, Theme};
+use crate::builders::utils::{contact_links, ContactItem};
+use crate::helpers;
+use genpdf::{
+    elements::{PaddedElement, Paragraph},
+    error::Error,
+    render::Area,
+    style::Style,
+    Alignment, Context, Document, Element, Mm, PageDecorator, Position,
+};
+use pdfshrink::gs_command;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{cell::Cell, fs, rc::Rc};
+
+/// The space between the header or footer and the page content, in mm.
+const GAP: f64 = 4.0;
+
+/// Headers and footers for one type of document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "snake_case")]
+pub struct PageOptions {
+    /// Repeat the name and contact line at the top of continuation pages.
+    pub header: bool,
+    /// Number the pages of documents that run over one page.
+    pub page_numbers: bool,
+    /// The page number text; `{page}` and `{pages}` are filled in.
+    pub page_format: String,
+}
+
+impl Default for PageOptions {
+    fn default() -> Self {
+        Self {
+            header: true,
+            page_numbers: true,
+            page_format: "Page {page} of {pages}".to_string(),
+        }
+    }
+}
+
+/// The `pages:` block of the config, one entry per document type.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "snake_case")]
+pub struct Pages {
+    pub resume: PageOptions,
+    pub cover_letter: PageOptions,
+    /// The cover letter and resume together, from `cv packet`.
+    pub packet: PageOptions,
+}
+
+/// Reserves a footer for page numbers on every page and, from the second
+/// page on, prints the name and contact line at the top.
+#[derive(Clone)]
+pub struct Decorator {
+    options: PageOptions,
+    name: String,
+    contact: Vec<ContactItem>,
+    theme: Theme,
+    margins: Spacing,
+    /// Unknown until the document has been rendered once.
+    total: Option<usize>,
+    /// Pages ahead of this document in a packet.
+    before: usize,
+    page: usize,
+}
+
+impl Decorator {
+    /// `margins` line the header and footer up with the document's content.
+    pub fn new(
+        options: &PageOptions,
+        name: &str,
+        contact: &[ContactItem],
+        theme: &Theme,
+        margins: Spacing,
+    ) -> Self {
+        Self {
+            options: options.clone(),
+            name: name.to_string(),
+            contact: contact.to_vec(),
+            theme: *theme,
+            margins,
+            total: None,
+            before: 0,
+            page: 0,
+        }
+    }
+
+    /// Numbers the pages as part of a packet of `total` pages, after the
+    /// `before` pages of the documents ahead of this one.
+    pub fn in_packet(mut self, before: usize, total: Option<usize>) -> Self {
+        self.before = before;
+        self.total = total;
+        self
+    }
+
+    fn style(&self) -> Style {
+        Style::new()
+            .with_font_size(self.theme.font_sizes.label)
+            .with_color(self.theme.colours.muted.into())
+            .with_line_spacing(1.0)
+    }
+}
+
+impl PageDecorator for Decorator {
+    fn decorate_page<'a>(
+        &mut self,
+        context: &Context,
+        mut area: Area<'a>,
+        style: Style,
+    ) -> Result<Area<'a>, Error> {
+        self.page += 1;
+        let style = style.and(self.style());
+
+        if self.options.page_numbers {
+            // The footer's space is kept even when there's no number to show,
+            // so that both rendering passes break pages in the same places.
+            let height = style.line_height(&context.font_cache);
+            let bottom = area.size().height - height - Mm::from(GAP);
+            if let Some(total) = self.total.filter(|&total| total > 1) {
+                let text = self
+                    .options
+                    .page_format
+                    .replace("{page}", &(self.before + self.page).to_string())
+                    .replace("{pages}", &total.to_string());
+                let mut para = Paragraph::new(text);
+                para.set_alignment(Alignment::Center);
+                let mut footer = area.clone();
+                footer.add_offset(Position::new(0, bottom));
+                PaddedElement::new(para, self.margins).render(context, footer, style)?;
+            }
+            area.set_height(bottom - Mm::from(GAP));
+        }
+
+        if self.options.header && self.page > 1 {
+            area.add_offset(Position::new(0, GAP));
+            let mut line = LinkLine::default();
+            line.push_styled(
+                helpers::capitalize(&self.name),
+                Style::new()
+                    .bold()
+                    .with_color(self.theme.colours.name.into()),
+                None,
+            );
+            if !self.contact.is_empty() {
+                line.push_separator("  |  ");
+                line.append(contact_links(&self.contact));
+            }
+            let header =
+                PaddedElement::new(line, self.margins).render(context, area.clone(), style)?;
+            area.add_offset(Position::new(0, header.size.height + Mm::from(GAP)));
+        }
+
+        Ok(area)
+    }
+}
+
+/// Wraps a page decorator to count the pages as they're laid out.
+struct PageCounter<D> {
+    inner: D,
+    pages: Rc<Cell<usize>>,
+}
+
+impl<D: PageDecorator> PageDecorator for PageCounter<D> {
+    fn decorate_page<'a>(
+        &mut self,
+        context: &Context,
+        area: Area<'a>,
+        style: Style,
+    ) -> Result<Area<'a>, Error> {
+        self.pages.set(self.pages.get() + 1);
+        self.inner.decorate_page(context, area, style)
+    }
+}
+
+/// A rendered PDF and how many pages it came to.
+pub struct Rendered {
+    pub pdf: Vec<u8>,
+    pub pages: usize,
+}
+
+/// Renders the document made by `document`, a second time when it runs over
+/// a page so that the footers can give the page count.
+pub fn render_numbered(
+    document: impl Fn() -> Document,
+    decorator: Decorator,
+    metadata: &Metadata,
+) -> Result<Rendered, String> {
+    let numbered = decorator.options.page_numbers;
+    let first = render(document(), decorator.clone(), metadata)?;
+    if !numbered || first.pages <= 1 {
+        return Ok(first);
+    }
+    render(
+        document(),
+        Decorator {
+            total: Some(first.pages),
+            ..decorator
+        },
+        metadata,
+    )
+}
+
+/// Renders `doc` in memory, decorating each page with `decorator`, making its
+/// links clickable and filling in its metadata.
+pub fn render<D>(mut doc: Document, decorator: D, metadata: &Metadata) -> Result<Rendered, String>
+where
+    D: PageDecorator + 'static,
+{
+    let pages = Rc::new(Cell::new(0));
+    doc.set_page_decorator(PageCounter {
+        inner: decorator,
+        pages: Rc::clone(&pages),
+    });
+    let mut pdf: Vec<u8> = Vec::new();
+    let (rendered, links) = links::collect(|| doc.render(&mut pdf));
+    rendered.map_err(|e| e.to_string())?;
+
+    let mut output = lopdf::Document::load_mem(&pdf).map_err(|e| e.to_string())?;
+    links::annotate(&mut output, &links)?;
+    metadata.apply(&mut output)?;
+    output.compress();
+    let mut pdf: Vec<u8> = Vec::new();
+    output.save_to(&mut pdf).map_err(|e| e.to_string())?;
+    Ok(Rendered {
+        pdf,
+        pages: pages.get(),
+    })
+}
+
+/// How `save` dealt with the Ghostscript step.
+pub enum Optimization {
+    /// Not asked for; the PDF was written as rendered.
+    Skipped,
+    /// Ghostscript rewrote the PDF.
+    Done,
+    /// Ghostscript was missing or failed, so the PDF was written as rendered.
+    Failed(String),
+}
+
+/// Where `save` wrote a PDF.
+pub struct Saved {
+    pub path: PathBuf,
+    pub optimization: Optimization,
+}
+
+/// The installed Ghostscript's version, or `None` when `gs` can't be run.
+pub fn ghostscript() -> Option<String> {
+    let output = Command::new("gs").arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Has Ghostscript downsample the images in `pdf` and write it to `output`.
+fn optimize(pdf: &[u8], output: &Path) -> Result<(), String> {
+    if ghostscript().is_none() {
+        return Err("Ghostscript (`gs`) isn't installed or isn't on your PATH".to_string());
+    }
+    let input = tempfile::Builder::new()
+        .suffix(".pdf")
+        .tempfile()
+        .map_err(|e| format!("unable to create a temporary file: {}", e))?;
+    fs::write(input.path(), pdf).map_err(|e| format!("unable to write a temporary file: {}", e))?;
+
+    let run = gs_command(input.path(), output)
+        .output()
+        .map_err(|e| format!("unable to run gs: {}", e))?;
+    if !run.status.success() {
+        let stderr = String::from_utf8_lossy(&run.stderr);
+        return Err(match stderr.trim() {
+            "" => format!("gs failed ({})", run.status),
+            stderr => format!("gs failed ({}):\n{}", run.status, stderr),
+        });
+    }
+    match fs::metadata(output) {
+        Ok(written) if written.len() > 0 => Ok(()),
+        _ => Err("gs didn't write the PDF".to_string()),
+    }
+}
+
+/// Writes a rendered PDF to `out`, adding a `.pdf` extension when it has
+/// another one. With `optimize`, Ghostscript writes it instead when it can.
+pub fn save(pdf: &[u8], out: PathBuf, optimize: bool) -> Result<Saved, String> {
+    let path = Format::Pdf.path(out);
+
+    let optimization = if optimize {
+        match self::optimize(pdf, &path) {
+            Ok(()) => {
+                return Ok(Saved {
+                    path,
+                    optimization: Optimization::Done,
+                })
+            }
+            Err(e) => Optimization::Failed(e),
+        }
+    } else {
+        Optimization::Skipped
+    };
+
+    fs::write(&path, pdf).map_err(|e| e.to_string())?;
+    Ok(Saved { path, optimization })
+}
@@ -0,0 +1,228 @@
+use genpdf::{PaperSize, Scale, Size};
+use serde::{Deserialize, Serialize};
+use std::{env, fmt, str::FromStr};
+
+/// Regions that use Letter paper; everywhere else uses A4.
+#[rustfmt::skip]
+static LETTER_TERRITORIES: [&str; 14] = [
+    "US", "CA", "PR", "MX", "CL", "CO", "CR", "DO", "GT", "NI", "PA", "PH", "SV", "VE",
+];
+
+/// Custom pages are kept between these, in mm. The largest is 200in, the
+/// most a PDF page can be.
+const MIN_SIDE: f64 = 50.0;
+const MAX_SIDE: f64 = 5080.0;
+
+/// A page size: `letter`, `a4`, `legal`, or a custom `<width>x<height>` in
+/// millimetres such as `148x210`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Paper {
+    Letter,
+    A4,
+    Legal,
+    Custom { width: f64, height: f64 },
+}
+
+impl Paper {
+    pub fn size(&self) -> Size {
+        match self {
+            Paper::Letter => PaperSize::Letter.into(),
+            Paper::A4 => PaperSize::A4.into(),
+            Paper::Legal => PaperSize::Legal.into(),
+            Paper::Custom { width, height } => Size::new(*width, *height),
+        }
+    }
+
+    /// How much wider or narrower the page is than Letter, which the layouts
+    /// were designed for.
+    pub fn ratio(&self) -> f64 {
+        f64::from(self.size().width) / f64::from(Paper::Letter.size().width)
+    }
+
+    /// An image scale that takes up the same share of the page width as
+    /// `x` does on Letter paper.
+    pub fn scale(&self, x: f64, y: f64) -> Scale {
+        Scale {
+            x: x * self.ratio(),
+            y,
+        }
+    }
+
+    /// The paper size for the user's locale, from `LC_ALL`, `LC_PAPER` or
+    /// `LANG`. Letter when no locale is set.
+    pub fn from_locale() -> Self {
+        let locale = ["LC_ALL", "LC_PAPER", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty());
+        match locale {
+            Some(locale) => Paper::for_locale(&locale),
+            None => Paper::Letter,
+        }
+    }
+
+    /// The paper size for a locale name like `en_GB.UTF-8`.
+    pub fn for_locale(locale: &str) -> Self {
+        let territory = locale
+            .split(['.', '@'])
+            .next()
+            .and_then(|l| l.split('_').nth(1));
+        match territory {
+            Some(t) if !LETTER_TERRITORIES.contains(&t.to_ascii_uppercase().as_str()) => Paper::A4,
+            _ => Paper::Letter,
+        }
+    }
+}
+
+impl From<Paper> for Size {
+    fn from(paper: Paper) -> Self {
+        paper.size()
+    }
+}
+
+impl fmt::Display for Paper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Paper::Letter => f.write_str("letter"),
+            Paper::A4 => f.write_str("a4"),
+            Paper::Legal => f.write_str("legal"),
+            Paper::Custom { width, height } => write!(f, "{}x{}", width, height),
+        }
+    }
+}
+
+impl FromStr for Paper {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        match s.as_str() {
+            "letter" => return Ok(Paper::Letter),
+            "a4" => return Ok(Paper::A4),
+            "legal" => return Ok(Paper::Legal),
+            _ => {}
+        }
+        let dims = s.strip_suffix("mm").unwrap_or(&s);
+        let parsed = dims
+            .split_once('x')
+            .and_then(|(w, h)| Some((w.trim().parse::<f64>().ok()?, h.trim().parse::<f64>().ok()?)))
+            .filter(|(width, height)| width.is_finite() && height.is_finite());
+        match parsed {
+            Some((width, height)) if width < MIN_SIDE || height < MIN_SIDE => Err(format!(
+                "`{}` is too small; pages must be at least {}x{}mm",
+                s, MIN_SIDE, MIN_SIDE
+            )),
+            Some((width, height)) if width > MAX_SIDE || height > MAX_SIDE => Err(format!(
+                "`{}` is too large; pages can be at most {}x{}mm",
+                s, MAX_SIDE, MAX_SIDE
+            )),
+            Some((width, height)) => Ok(Paper::Custom { width, height }),
+            None => Err(format!(
+                "unknown paper size `{}` (expected letter, a4, legal or <width>x<height> in mm)",
+                s
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for Paper {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Paper> for String {
+    fn from(paper: Paper) -> Self {
+        paper.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_named_sizes() {
+        assert_eq!("letter".parse(), Ok(Paper::Letter));
+        assert_eq!(" A4 ".parse(), Ok(Paper::A4));
+        assert_eq!("Legal".parse(), Ok(Paper::Legal));
+    }
+
+    #[test]
+    fn parses_custom_sizes() {
+        assert_eq!(
+            "210x297".parse(),
+            Ok(Paper::Custom {
+                width: 210.0,
+                height: 297.0
+            })
+        );
+        assert_eq!(
+            "148.5 x 210mm".parse(),
+            Ok(Paper::Custom {
+                width: 148.5,
+                height: 210.0
+            })
+        );
+        assert_eq!(
+            Paper::Custom {
+                width: 210.0,
+                height: 297.0
+            }
+            .to_string(),
+            "210x297"
+        );
+    }
+
+    #[test]
+    fn rejects_unusable_sizes() {
+        for size in [
+            "10x10",
+            "40x297",
+            "6000x297",
+            "infx300",
+            "300xinfinity",
+            "NaNx300",
+        ] {
+            assert!(size.parse::<Paper>().is_err(), "{} was accepted", size);
+        }
+        assert_eq!(
+            "10x10".parse::<Paper>(),
+            Err("`10x10` is too small; pages must be at least 50x50mm".to_string())
+        );
+        assert_eq!(
+            "infx300".parse::<Paper>(),
+            Err("unknown paper size `infx300` (expected letter, a4, legal or <width>x<height> in mm)".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        for size in ["", "a5", "x", "210x", "210 by 297", "-210x297x1"] {
+            assert!(size.parse::<Paper>().is_err(), "{} was accepted", size);
+        }
+    }
+
+    #[test]
+    fn picks_paper_by_locale() {
+        assert_eq!(Paper::for_locale("en_US.UTF-8"), Paper::Letter);
+        assert_eq!(Paper::for_locale("es_mx"), Paper::Letter);
+        assert_eq!(Paper::for_locale("en_GB.UTF-8"), Paper::A4);
+        assert_eq!(Paper::for_locale("de_DE@euro"), Paper::A4);
+        assert_eq!(Paper::for_locale("C"), Paper::Letter);
+        assert_eq!(Paper::for_locale("POSIX"), Paper::Letter);
+    }
+
+    #[test]
+    fn defaults_to_the_locale_from_the_environment() {
+        // The only test that touches these variables
+        env::set_var("LC_ALL", "fr_FR.UTF-8");
+        assert_eq!(Paper::from_locale(), Paper::A4);
+        env::set_var("LC_ALL", "en_CA.UTF-8");
+        assert_eq!(Paper::from_locale(), Paper::Letter);
+        env::remove_var("LC_ALL");
+    }
+}
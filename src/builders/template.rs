@@ -0,0 +1,346 @@
+use crate::builders::FILES_DIR;
+use std::collections::BTreeMap;
+
+/// The values substituted into a template, keyed by variable name. A variable
+/// that is known but unset renders as an empty string and is falsy in
+/// `{{#if}}` blocks; referencing a variable that isn't a key is an error.
+pub type Context = BTreeMap<&'static str, Option<String>>;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Text(String),
+    Var(String),
+    If {
+        var: String,
+        negate: bool,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Text(String),
+    Var(String),
+    Open { var: String, negate: bool },
+    Else,
+    Close { negate: bool },
+}
+
+/// A parsed cover letter template.
+///
+/// Supports `{{variable}}` substitution and conditional blocks:
+///
+/// ```text
+/// {{#if position}}the {{position}} role{{else}}a role{{/if}}
+/// {{#unless location}}remotely{{/unless}}
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut iter = tokens.into_iter();
+        let (nodes, end) = parse_nodes(&mut iter)?;
+        match end {
+            None => Ok(Self { nodes }),
+            Some(Token::Else) => Err("`{{else}}` outside of an `{{#if}}` block".to_string()),
+            Some(_) => Err("closing tag without a matching opening block".to_string()),
+        }
+    }
+
+    /// The bundled template used when none is configured.
+    pub fn default_cover_letter() -> Self {
+        let source = FILES_DIR
+            .get_file("coverletter.txt")
+            .and_then(|f| f.contents_utf8())
+            .expect("The bundled cover letter template is missing");
+        Self::parse(source).expect("The bundled cover letter template is invalid")
+    }
+
+    pub fn render(&self, context: &Context) -> Result<String, String> {
+        let mut out = String::new();
+        render_nodes(&self.nodes, context, &mut out)?;
+        Ok(out)
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            tokens.push(Token::Text(rest[..start].to_string()));
+        }
+        let after = &rest[start + 2..];
+        let end = match after.find("}}") {
+            Some(end) => end,
+            None => {
+                return Err(format!(
+                    "unclosed tag `{{{{{}`",
+                    after.lines().next().unwrap_or("")
+                ))
+            }
+        };
+        let tag = after[..end].trim();
+        let mut words = tag.split_whitespace();
+        let token = match (words.next(), words.next(), words.next()) {
+            (Some("#if"), Some(var), None) => Token::Open {
+                var: var.to_string(),
+                negate: false,
+            },
+            (Some("#unless"), Some(var), None) => Token::Open {
+                var: var.to_string(),
+                negate: true,
+            },
+            (Some("else"), None, None) => Token::Else,
+            (Some("/if"), None, None) => Token::Close { negate: false },
+            (Some("/unless"), None, None) => Token::Close { negate: true },
+            (Some(var), None, None) if !var.starts_with(['#', '/']) => Token::Var(var.to_string()),
+            _ => return Err(format!("unrecognised tag `{{{{{}}}}}`", tag)),
+        };
+        tokens.push(token);
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest.to_string()));
+    }
+    Ok(tokens)
+}
+
+fn parse_nodes(
+    tokens: &mut impl Iterator<Item = Token>,
+) -> Result<(Vec<Node>, Option<Token>), String> {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Text(text) => nodes.push(Node::Text(text)),
+            Token::Var(var) => nodes.push(Node::Var(var)),
+            Token::Open { var, negate } => {
+                let (then, end) = parse_nodes(tokens)?;
+                let (otherwise, end) = match end {
+                    Some(Token::Else) => parse_nodes(tokens)?,
+                    end => (Vec::new(), end),
+                };
+                match end {
+                    Some(Token::Close { negate: closed }) if closed == negate => {}
+                    _ => {
+                        let kind = if negate { "unless" } else { "if" };
+                        return Err(format!(
+                            "`{{{{#{} {}}}}}` is missing its `{{{{/{}}}}}`",
+                            kind, var, kind
+                        ));
+                    }
+                }
+                nodes.push(Node::If {
+                    var,
+                    negate,
+                    then,
+                    otherwise,
+                });
+            }
+            end @ (Token::Else | Token::Close { .. }) => return Ok((nodes, Some(end))),
+        }
+    }
+    Ok((nodes, None))
+}
+
+fn lookup<'a>(var: &str, context: &'a Context) -> Result<Option<&'a str>, String> {
+    match context.get(var) {
+        Some(value) => Ok(value.as_deref().filter(|v| !v.trim().is_empty())),
+        None => Err(format!(
+            "unknown variable `{}` (expected one of: {})",
+            var,
+            context.keys().cloned().collect::<Vec<&str>>().join(", ")
+        )),
+    }
+}
+
+fn render_nodes(nodes: &[Node], context: &Context, out: &mut String) -> Result<(), String> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var(var) => out.push_str(lookup(var, context)?.unwrap_or_default()),
+            Node::If {
+                var,
+                negate,
+                then,
+                otherwise,
+            } => {
+                if lookup(var, context)?.is_some() != *negate {
+                    render_nodes(then, context, out)?;
+                } else {
+                    render_nodes(otherwise, context, out)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Splits rendered template text into paragraphs on blank lines, joining the
+/// lines within each paragraph with a single space.
+pub fn paragraphs(text: &str) -> Vec<String> {
+    let mut paras = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            if !current.is_empty() {
+                paras.push(current.join(" "));
+                current.clear();
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        paras.push(current.join(" "));
+    }
+    paras
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(position: Option<&str>, location: Option<&str>) -> Context {
+        let mut context = Context::new();
+        context.insert("company", Some("Acme".to_string()));
+        context.insert("position", position.map(str::to_string));
+        context.insert("location", location.map(str::to_string));
+        context
+    }
+
+    fn render(source: &str, context: &Context) -> Result<String, String> {
+        Template::parse(source)?.render(context)
+    }
+
+    #[test]
+    fn substitutes_variables() {
+        let context = context(Some("Engineer"), None);
+        assert_eq!(
+            render("Dear {{company}}, re: {{ position }}.", &context),
+            Ok("Dear Acme, re: Engineer.".to_string())
+        );
+        // Known but unset variables render as nothing
+        assert_eq!(render("[{{location}}]", &context), Ok("[]".to_string()));
+    }
+
+    #[test]
+    fn rejects_unknown_variables() {
+        assert_eq!(
+            render("{{salary}}", &context(None, None)),
+            Err(
+                "unknown variable `salary` (expected one of: company, location, position)"
+                    .to_string()
+            )
+        );
+        assert!(render("{{#if salary}}x{{/if}}", &context(None, None)).is_err());
+    }
+
+    #[test]
+    fn if_blocks_follow_the_value() {
+        let source = "{{#if position}}the {{position}} role{{else}}a role{{/if}}";
+        assert_eq!(
+            render(source, &context(Some("Engineer"), None)),
+            Ok("the Engineer role".to_string())
+        );
+        assert_eq!(
+            render(source, &context(None, None)),
+            Ok("a role".to_string())
+        );
+        // Blank values are falsy too
+        assert_eq!(
+            render(source, &context(Some("  "), None)),
+            Ok("a role".to_string())
+        );
+        assert_eq!(
+            render("{{#if position}}set{{/if}}", &context(None, None)),
+            Ok(String::new())
+        );
+    }
+
+    #[test]
+    fn unless_blocks_invert_the_value() {
+        let source = "{{#unless location}}remotely{{else}}in {{location}}{{/unless}}";
+        assert_eq!(
+            render(source, &context(None, None)),
+            Ok("remotely".to_string())
+        );
+        assert_eq!(
+            render(source, &context(None, Some(""))),
+            Ok("remotely".to_string())
+        );
+        assert_eq!(
+            render(source, &context(None, Some("Berlin"))),
+            Ok("in Berlin".to_string())
+        );
+    }
+
+    #[test]
+    fn blocks_nest() {
+        let source = "{{#if position}}{{position}}{{#unless location}} (remote){{else}} in {{location}}{{/unless}}{{else}}any role{{#if location}} in {{location}}{{/if}}{{/if}}";
+        assert_eq!(
+            render(source, &context(Some("Engineer"), None)),
+            Ok("Engineer (remote)".to_string())
+        );
+        assert_eq!(
+            render(source, &context(Some("Engineer"), Some("Oslo"))),
+            Ok("Engineer in Oslo".to_string())
+        );
+        assert_eq!(
+            render(source, &context(None, Some("Oslo"))),
+            Ok("any role in Oslo".to_string())
+        );
+        assert_eq!(
+            render(source, &context(None, None)),
+            Ok("any role".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_tags() {
+        for source in [
+            "Dear {{company",
+            "{{#if position}}no end",
+            "{{#if position}}x{{/unless}}",
+            "{{#unless position}}x{{/if}}",
+            "stray {{/if}}",
+            "stray {{else}}",
+            "{{#if}}x{{/if}}",
+            "{{#each position}}x{{/each}}",
+            "{{company position}}",
+        ] {
+            assert!(Template::parse(source).is_err(), "{} parsed", source);
+        }
+        assert_eq!(
+            Template::parse("{{#if position}}x{{/unless}}"),
+            Err("`{{#if position}}` is missing its `{{/if}}`".to_string())
+        );
+    }
+
+    #[test]
+    fn splits_paragraphs_on_blank_lines() {
+        let text = "\n  First line\nwraps here.\n\n\n  Second.  \n   \nThird\n";
+        assert_eq!(
+            paragraphs(text),
+            vec!["First line wraps here.", "Second.", "Third"]
+        );
+        assert!(paragraphs(" \n\n ").is_empty());
+    }
+
+    #[test]
+    fn bundled_template_renders() {
+        let mut context = context(Some("Engineer"), Some("Oslo"));
+        context.insert("hiring_manager", None);
+        context.insert("name", Some("Jane Doe".to_string()));
+        context.insert("date", Some("1 January 2024".to_string()));
+        let text = Template::default_cover_letter().render(&context).unwrap();
+        assert!(text.contains("Acme"));
+        assert!(!paragraphs(&text).is_empty());
+    }
+}
@@ -0,0 +1,469 @@
+use crate::builders::theme::{Colour, Spacing, Theme};
+use crate::builders::utils::{marked_spans, ContactItem, Degree, Project, Skill, WorkExperience};
+use crate::builders::Settings;
+use crate::config::Resume;
+use crate::helpers;
+use std::fmt::Write;
+
+/// `text` with LaTeX's special characters escaped, so user text always
+/// compiles.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '<' => escaped.push_str("\\textless{}"),
+            '>' => escaped.push_str("\\textgreater{}"),
+            '|' => escaped.push_str("\\textbar{}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A URL for `\href`, which only needs the characters that end or comment
+/// out its argument escaped.
+fn url(href: &str) -> String {
+    let mut escaped = String::with_capacity(href.len());
+    for c in href.chars() {
+        if matches!(c, '\\' | '%' | '#' | '{' | '}') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// `text` with its `**emphasised**` spans in bold.
+fn marked(text: &str) -> String {
+    marked_spans(text)
+        .map(|(span, bold)| match bold {
+            true => format!("\\textbf{{{}}}", escape(span)),
+            false => escape(span),
+        })
+        .collect()
+}
+
+fn link(text: &str, href: Option<String>) -> String {
+    match href {
+        Some(href) => format!("\\href{{{}}}{{{}}}", url(&href), escape(text)),
+        None => escape(text),
+    }
+}
+
+fn contact_markup(items: &[ContactItem]) -> String {
+    items
+        .iter()
+        .map(|item| link(&item.label, item.href()))
+        .collect::<Vec<String>>()
+        .join(" \\textbar{} ")
+}
+
+fn colour(c: Colour) -> String {
+    format!("{:02X}{:02X}{:02X}", c.0, c.1, c.2)
+}
+
+/// A font size switch, with the theme's line spacing.
+fn size(points: u8, line_spacing: f64) -> String {
+    format!(
+        "\\fontsize{{{}}}{{{:.1}}}\\selectfont",
+        points,
+        f64::from(points) * line_spacing
+    )
+}
+
+/// The packages, page and colours shared by both documents. Compiles with
+/// pdfLaTeX, XeLaTeX or LuaLaTeX.
+fn preamble(settings: &Settings, theme: &Theme, margins: &Spacing) -> String {
+    let paper = settings.paper.size();
+    let metadata = &settings.metadata;
+    let mut tex = String::from("% Generated by ");
+    writeln!(
+        tex,
+        "{} {}",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    )
+    .unwrap();
+    tex.push_str(
+        "\\documentclass{article}
+\\usepackage{iftex}
+\\ifPDFTeX
+  \\usepackage[T1]{fontenc}
+  \\usepackage[utf8]{inputenc}
+  \\usepackage{lmodern}
+\\else
+  \\usepackage{fontspec}
+\\fi
+\\usepackage{xcolor}
+\\usepackage{enumitem}
+",
+    );
+    writeln!(
+        tex,
+        "\\usepackage[paperwidth={:.1}mm, paperheight={:.1}mm, top=10mm, bottom=10mm, left={:.1}mm, right={:.1}mm]{{geometry}}",
+        f64::from(paper.width),
+        f64::from(paper.height),
+        margins.left,
+        margins.right
+    )
+    .unwrap();
+    tex.push_str("\\usepackage[hidelinks]{hyperref}\n");
+    let colours = &theme.colours;
+    for (name, c) in [
+        ("name", colours.name),
+        ("heading", colours.heading),
+        ("text", colours.text),
+        ("muted", colours.muted),
+        ("accent", colours.accent),
+    ] {
+        writeln!(tex, "\\definecolor{{{}}}{{HTML}}{{{}}}", name, colour(c)).unwrap();
+    }
+    writeln!(
+        tex,
+        "\\hypersetup{{pdftitle={{{}}}, pdfauthor={{{}}}, pdfsubject={{{}}}, pdfkeywords={{{}}}}}",
+        escape(&metadata.title),
+        escape(&metadata.author),
+        escape(&metadata.subject),
+        escape(&metadata.keywords.join(", "))
+    )
+    .unwrap();
+    tex.push_str(
+        "\\setlength{\\parindent}{0pt}
+\\setlist[itemize]{leftmargin=1.2em, nosep}
+\\pagestyle{empty}
+",
+    );
+    tex
+}
+
+fn skills(skills: &[Skill]) -> String {
+    let mut tex = String::new();
+    for skill in skills {
+        writeln!(
+            tex,
+            "\\textbf{{{}}}{}\\par",
+            escape(&skill.section),
+            escape(&skill.skills.join(", "))
+        )
+        .unwrap();
+    }
+    tex
+}
+
+fn employment(jobs: &[WorkExperience]) -> String {
+    let mut tex = String::new();
+    for job in jobs {
+        writeln!(
+            tex,
+            "\\cventry{{{}, {}}}{{{} --- {} -- {}}}",
+            escape(&job.position),
+            escape(&job.company),
+            escape(&job.location),
+            escape(&job.start_date),
+            escape(&job.end_date)
+        )
+        .unwrap();
+        if !job.highlights.is_empty() {
+            tex.push_str("\\begin{itemize}\n");
+            for highlight in &job.highlights {
+                // The braces stop a leading `[` being read as the item's label
+                writeln!(tex, "  \\item{{}} {}", marked(highlight)).unwrap();
+            }
+            tex.push_str("\\end{itemize}\n");
+        }
+    }
+    tex
+}
+
+fn education(degrees: &[Degree]) -> String {
+    let mut tex = String::new();
+    for degree in degrees {
+        writeln!(
+            tex,
+            "\\cventry{{{}, {}}}{{{}, {}}}",
+            escape(&degree.university),
+            escape(&degree.degree),
+            escape(&degree.location),
+            escape(&degree.year)
+        )
+        .unwrap();
+        if !degree.description.is_empty() {
+            writeln!(tex, "{}\\par", escape(&degree.description)).unwrap();
+        }
+    }
+    tex
+}
+
+fn projects(projects: &[Project]) -> String {
+    let mut tex = String::new();
+    for project in projects {
+        let title = match &project.nickname {
+            Some(nickname) => format!(
+                "\\textit{{{} -}} {}",
+                escape(nickname),
+                escape(&project.title)
+            ),
+            None => escape(&project.title),
+        };
+        writeln!(
+            tex,
+            "\\cventry{{{}}}{{{} -- {}}}",
+            title,
+            escape(&project.organization),
+            escape(&project.year)
+        )
+        .unwrap();
+        if let Some(description) = &project.description {
+            writeln!(tex, "{}\\par", marked(description)).unwrap();
+        }
+        if let Some(href) = &project.url {
+            writeln!(
+                tex,
+                "{{\\color{{muted}}{}}}\\par",
+                link(href, Some(href.clone()))
+            )
+            .unwrap();
+        }
+    }
+    tex
+}
+
+/// The resume as a LaTeX source file, styled by the theme, for finishing
+/// by hand.
+pub fn resume(
+    name: &str,
+    position: &str,
+    resume: &Resume,
+    contact: &[ContactItem],
+    settings: &Settings,
+) -> String {
+    let theme = &settings.layout.theme(&settings.theme);
+    let sizes = &theme.font_sizes;
+    let line_spacing = theme.layout.line_spacing;
+    let mut tex = preamble(settings, theme, &theme.layout.header);
+    writeln!(
+        tex,
+        "\\newcommand{{\\cvsection}}[1]{{\\par\\vspace{{1.2ex}}{{{}\\bfseries\\color{{heading}}#1\\par}}\\vspace{{0.4ex}}{{\\color{{heading}}\\hrule}}\\vspace{{0.8ex}}}}",
+        size(sizes.section, 1.0)
+    )
+    .unwrap();
+    writeln!(
+        tex,
+        "\\newcommand{{\\cventry}}[2]{{\\par\\vspace{{0.6ex}}{{{}\\bfseries\\color{{heading}}#1}} \\hfill #2\\par}}",
+        size(sizes.entry, 1.2)
+    )
+    .unwrap();
+
+    tex.push_str("\n\\begin{document}\n\\color{text}\n");
+    writeln!(
+        tex,
+        "{{{}\\bfseries\\color{{name}}{}\\par}}",
+        size(sizes.name, 1.2),
+        escape(&helpers::capitalize(name))
+    )
+    .unwrap();
+    tex.push_str("{\\color{heading}\\hrule}\\vspace{1ex}\n");
+    if !contact.is_empty() {
+        writeln!(
+            tex,
+            "{{{}\\color{{muted}}{}\\par}}",
+            size(sizes.contact, 1.2),
+            contact_markup(contact)
+        )
+        .unwrap();
+    }
+    writeln!(tex, "{}", size(sizes.body, line_spacing)).unwrap();
+
+    writeln!(
+        tex,
+        "\n\\cvsection{{{}}}",
+        escape(&helpers::capitalize(position))
+    )
+    .unwrap();
+    if let Some(summary) = &resume.summary {
+        writeln!(tex, "{}\\par", marked(summary)).unwrap();
+    }
+    if !resume.skills.is_empty() {
+        write!(tex, "\n\\cvsection{{SKILLS}}\n{}", skills(&resume.skills)).unwrap();
+    }
+    if !resume.employment.is_empty() {
+        write!(
+            tex,
+            "\n\\cvsection{{EXPERIENCE}}\n{}",
+            employment(&resume.employment)
+        )
+        .unwrap();
+    }
+    if !resume.education.is_empty() {
+        write!(
+            tex,
+            "\n\\cvsection{{EDUCATION}}\n{}",
+            education(&resume.education)
+        )
+        .unwrap();
+    }
+    if !resume.projects.is_empty() {
+        write!(
+            tex,
+            "\n\\cvsection{{PROJECTS}}\n{}",
+            projects(&resume.projects)
+        )
+        .unwrap();
+    }
+    tex.push_str("\n\\end{document}\n");
+    tex
+}
+
+/// The cover letter as a LaTeX source file, with the PDF's heading, body and
+/// contact block.
+pub fn cover_letter(
+    name: &str,
+    company: &str,
+    location: Option<&str>,
+    hiring_manager: Option<&str>,
+    body: &[String],
+    contact: &[ContactItem],
+    settings: &Settings,
+) -> String {
+    let theme = &settings.theme;
+    let sizes = &theme.font_sizes;
+    let mut tex = preamble(settings, theme, &theme.layout.letter);
+    tex.push_str("\\setlength{\\parskip}{1em}\n");
+
+    tex.push_str("\n\\begin{document}\n\\color{text}\n\\begin{center}\n");
+    writeln!(
+        tex,
+        "{{{}\\bfseries\\color{{accent}}FROM THE DESK OF\\par}}",
+        size(sizes.label, 1.5)
+    )
+    .unwrap();
+    writeln!(
+        tex,
+        "{{{}\\bfseries\\color{{name}}{}\\par}}",
+        size(sizes.letter_name, 1.2),
+        escape(&helpers::capitalize(name))
+    )
+    .unwrap();
+    tex.push_str("\\end{center}\n");
+    writeln!(
+        tex,
+        "{}",
+        size(sizes.letter_body, theme.layout.letter_line_spacing)
+    )
+    .unwrap();
+
+    writeln!(tex, "\n{}\n", escape(&helpers::today())).unwrap();
+    match location {
+        Some(location) => writeln!(tex, "{}\\newline\n{}\n", escape(company), escape(location)),
+        None => writeln!(tex, "{}\n", escape(company)),
+    }
+    .unwrap();
+    writeln!(tex, "Dear {},\n", escape(hiring_manager.unwrap_or(company))).unwrap();
+    for paragraph in body {
+        writeln!(tex, "{}\n", escape(paragraph)).unwrap();
+    }
+    writeln!(tex, "Warm regards,\n\n{}", escape(name)).unwrap();
+
+    if !contact.is_empty() {
+        tex.push_str("\n\\vfill\n\\begin{center}\n");
+        writeln!(
+            tex,
+            "{{{}\\bfseries\\color{{accent}}CONTACT\\par}}",
+            size(sizes.label, 1.5)
+        )
+        .unwrap();
+        writeln!(
+            tex,
+            "{{{}\\bfseries\\color{{name}}{}\\par}}",
+            size(sizes.label, 1.2),
+            contact_markup(contact)
+        )
+        .unwrap();
+        tex.push_str("\\end{center}\n");
+    }
+    tex.push_str("\\end{document}\n");
+    tex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigFile;
+
+    /// Whether every `c` in `tex` is escaped with a backslash.
+    fn all_escaped(tex: &str, c: char) -> bool {
+        tex.match_indices(c).all(|(i, _)| tex[..i].ends_with('\\'))
+    }
+
+    #[test]
+    fn escapes_every_special_character() {
+        for (text, tex) in [
+            ("&", "\\&"),
+            ("%", "\\%"),
+            ("$", "\\$"),
+            ("#", "\\#"),
+            ("_", "\\_"),
+            ("{", "\\{"),
+            ("}", "\\}"),
+            ("~", "\\textasciitilde{}"),
+            ("^", "\\textasciicircum{}"),
+            ("<", "\\textless{}"),
+            (">", "\\textgreater{}"),
+            ("|", "\\textbar{}"),
+            ("\\", "\\textbackslash{}"),
+            ("plain text", "plain text"),
+        ] {
+            assert_eq!(escape(text), tex, "escaping {}", text);
+        }
+        assert_eq!(escape("C:\\dir_1"), "C:\\textbackslash{}dir\\_1");
+    }
+
+    #[test]
+    fn escapes_only_what_ends_an_href() {
+        assert_eq!(
+            url("https://example.com/a_b?x=1&y=50%#top"),
+            "https://example.com/a_b?x=1&y=50\\%\\#top"
+        );
+    }
+
+    #[test]
+    fn escapes_inside_bold_spans() {
+        assert_eq!(
+            marked("Saved **30% & more** on R&D"),
+            "Saved \\textbf{30\\% \\& more} on R\\&D"
+        );
+    }
+
+    #[test]
+    fn highlights_cannot_break_the_resume() {
+        let cv = Resume {
+            employment: vec![WorkExperience {
+                position: "Engineer".to_string(),
+                company: "Acme".to_string(),
+                location: "Remote".to_string(),
+                start_date: "2020".to_string(),
+                end_date: "Present".to_string(),
+                highlights: vec!["Cut costs 30% & shipped foo_bar".to_string()],
+            }],
+            ..Resume::default()
+        };
+        let settings =
+            Settings::load(&ConfigFile::default(), &clap::ArgMatches::default()).unwrap();
+        let tex = resume("Jane Doe", "Engineer", &cv, &[], &settings);
+
+        let item = tex
+            .lines()
+            .find(|line| line.contains("Cut costs"))
+            .expect("the highlight is in the resume");
+        assert_eq!(item, "  \\item{} Cut costs 30\\% \\& shipped foo\\_bar");
+        let body = &tex[tex.find("\\begin{document}").unwrap()..];
+        assert!(all_escaped(body, '%') && all_escaped(body, '&') && all_escaped(body, '_'));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::builders::utils::{
+    contact_line, marked_spans, ContactItem, Degree, Project, Skill, WorkExperience,
+};
+use crate::config::Resume;
+use crate::helpers;
+use std::fmt::Write;
+
+/// `text` with Markdown's punctuation escaped, or as-is for plain text.
+fn escape(text: &str, markdown: bool) -> String {
+    if !markdown {
+        return text.to_string();
+    }
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// `text` with its `**emphasised**` spans kept in bold for Markdown, or the
+/// markers dropped for plain text.
+fn marked(text: &str, markdown: bool) -> String {
+    let mut out = String::new();
+    for (span, bold) in marked_spans(text) {
+        if bold && markdown {
+            write!(out, "**{}**", escape(span, markdown)).unwrap();
+        } else {
+            out.push_str(&escape(span, markdown));
+        }
+    }
+    out
+}
+
+fn contact_markup(items: &[ContactItem], markdown: bool) -> String {
+    if !markdown {
+        return contact_line(items);
+    }
+    items
+        .iter()
+        .map(|item| match item.href() {
+            Some(href) => format!("[{}]({})", escape(&item.label, markdown), href),
+            None => escape(&item.label, markdown),
+        })
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+/// A document or section heading: capitals for plain text, as the PDF has
+/// them.
+fn heading(level: usize, title: &str, markdown: bool) -> String {
+    match markdown {
+        true => format!("{} {}\n", "#".repeat(level), escape(title, markdown)),
+        false => format!("{}\n", helpers::capitalize(title)),
+    }
+}
+
+/// The gap between blocks: Markdown needs a blank line to end a paragraph.
+fn gap(markdown: bool) -> &'static str {
+    match markdown {
+        true => "\n",
+        false => "",
+    }
+}
+
+fn bullets(items: &[String], markdown: bool) -> String {
+    let mut text = String::new();
+    for item in items {
+        writeln!(text, "- {}", marked(item, markdown)).unwrap();
+    }
+    text
+}
+
+fn section(title: &str, body: &str, markdown: bool) -> String {
+    format!("\n{}{}{}", heading(2, title, markdown), gap(markdown), body)
+}
+
+fn skills(skills: &[Skill], markdown: bool) -> String {
+    let mut text = String::new();
+    for skill in skills {
+        let list = escape(&skill.skills.join(", "), markdown);
+        match markdown {
+            true => writeln!(
+                text,
+                "- **{}** {}",
+                escape(skill.section.trim(), markdown),
+                list
+            ),
+            false => writeln!(text, "{}{}", skill.section, list),
+        }
+        .unwrap();
+    }
+    text
+}
+
+fn employment(jobs: &[WorkExperience], markdown: bool) -> String {
+    let mut text = String::new();
+    for (i, job) in jobs.iter().enumerate() {
+        let title = format!("{}, {}", job.position, job.company);
+        let when = format!("{} — {} - {}", job.location, job.start_date, job.end_date);
+        if i > 0 {
+            text.push('\n');
+        }
+        if markdown {
+            text.push_str(&heading(3, &title, markdown));
+            writeln!(text, "\n{}", escape(&when, markdown)).unwrap();
+        } else {
+            writeln!(text, "{}; {}", title, when).unwrap();
+        }
+        if !job.highlights.is_empty() {
+            text.push_str(gap(markdown));
+            text.push_str(&bullets(&job.highlights, markdown));
+        }
+    }
+    text
+}
+
+fn education(degrees: &[Degree], markdown: bool) -> String {
+    let mut text = String::new();
+    for (i, degree) in degrees.iter().enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        let title = format!("{}, {}", degree.university, degree.degree);
+        let when = format!("{}, {}", degree.location, degree.year);
+        if markdown {
+            text.push_str(&heading(3, &title, markdown));
+            writeln!(text, "\n{}", escape(&when, markdown)).unwrap();
+        } else {
+            writeln!(text, "{} — {}", title, when).unwrap();
+        }
+        if !degree.description.is_empty() {
+            text.push_str(gap(markdown));
+            writeln!(text, "{}", escape(&degree.description, markdown)).unwrap();
+        }
+    }
+    text
+}
+
+fn projects(projects: &[Project], markdown: bool) -> String {
+    let mut text = String::new();
+    for (i, project) in projects.iter().enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        let title = match &project.nickname {
+            Some(nickname) => format!("{} - {}", nickname, project.title),
+            None => project.title.clone(),
+        };
+        let organization = format!("{} - {}", project.organization, project.year);
+        if markdown {
+            text.push_str(&heading(3, &title, markdown));
+            writeln!(text, "\n{}", escape(&organization, markdown)).unwrap();
+        } else {
+            writeln!(text, "{}\n{}", title, organization).unwrap();
+        }
+        if let Some(description) = &project.description {
+            text.push_str(gap(markdown));
+            writeln!(text, "{}", marked(description, markdown)).unwrap();
+        }
+        if let Some(url) = &project.url {
+            text.push_str(gap(markdown));
+            match markdown {
+                true => writeln!(text, "<{}>", url),
+                false => writeln!(text, "{}", url),
+            }
+            .unwrap();
+        }
+    }
+    text
+}
+
+/// The resume as plain text or Markdown, for pasting into application
+/// forms. The content matches the PDF, in the classic layout's order.
+pub fn resume(
+    name: &str,
+    position: &str,
+    resume: &Resume,
+    contact: &[ContactItem],
+    markdown: bool,
+) -> String {
+    let mut text = heading(1, name, markdown);
+    if !contact.is_empty() {
+        text.push_str(gap(markdown));
+        writeln!(text, "{}", contact_markup(contact, markdown)).unwrap();
+    }
+
+    let summary = match &resume.summary {
+        Some(summary) => format!("{}\n", marked(summary, markdown)),
+        None => String::new(),
+    };
+    text.push_str(&section(position, &summary, markdown));
+    if !resume.skills.is_empty() {
+        let skills = skills(&resume.skills, markdown);
+        text.push_str(&section("Skills", &skills, markdown));
+    }
+    if !resume.employment.is_empty() {
+        let employment = employment(&resume.employment, markdown);
+        text.push_str(&section("Experience", &employment, markdown));
+    }
+    if !resume.education.is_empty() {
+        let education = education(&resume.education, markdown);
+        text.push_str(&section("Education", &education, markdown));
+    }
+    if !resume.projects.is_empty() {
+        let projects = projects(&resume.projects, markdown);
+        text.push_str(&section("Projects", &projects, markdown));
+    }
+    text
+}
+
+/// The cover letter as plain text or Markdown, with the PDF's content.
+pub fn cover_letter(
+    name: &str,
+    company: &str,
+    location: Option<&str>,
+    hiring_manager: Option<&str>,
+    body: &[String],
+    contact: &[ContactItem],
+    markdown: bool,
+) -> String {
+    let mut text = heading(1, name, markdown);
+    writeln!(text, "\n{}\n", escape(&helpers::today(), markdown)).unwrap();
+    match (location, markdown) {
+        // A trailing backslash keeps the address on two lines in Markdown
+        (Some(location), true) => writeln!(
+            text,
+            "{}\\\n{}",
+            escape(company, markdown),
+            escape(location, markdown)
+        ),
+        (Some(location), false) => writeln!(text, "{}\n{}", company, location),
+        (None, _) => writeln!(text, "{}", escape(company, markdown)),
+    }
+    .unwrap();
+    let greeting = format!("Dear {},", hiring_manager.unwrap_or(company));
+    writeln!(text, "\n{}", escape(&greeting, markdown)).unwrap();
+    for paragraph in body {
+        writeln!(text, "\n{}", escape(paragraph, markdown)).unwrap();
+    }
+    writeln!(text, "\nWarm regards,\n\n{}", escape(name, markdown)).unwrap();
+
+    if !contact.is_empty() {
+        // Plain text has no rules; the blank line alone sets the contact apart
+        let rule = match markdown {
+            true => "\n---\n",
+            false => "",
+        };
+        writeln!(text, "{}\n{}", rule, contact_markup(contact, markdown)).unwrap();
+    }
+    text
+}
@@ -0,0 +1,401 @@
+use crate::config;
+use genpdf::{style, Margins};
+use serde::{
+    de::{self, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use std::{fmt, fs, path::PathBuf};
+
+/// An RGB colour, written as `"#367da2"` or `[54, 125, 162]` in config files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "ColourRepr", into = "String")]
+pub struct Colour(pub u8, pub u8, pub u8);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColourRepr {
+    Hex(String),
+    Rgb([u8; 3]),
+}
+
+impl TryFrom<ColourRepr> for Colour {
+    type Error = String;
+
+    fn try_from(repr: ColourRepr) -> Result<Self, Self::Error> {
+        match repr {
+            ColourRepr::Rgb([r, g, b]) => Ok(Colour(r, g, b)),
+            ColourRepr::Hex(hex) => {
+                let digits = hex.trim().trim_start_matches('#');
+                let channel = |i: usize| {
+                    digits
+                        .get(i..i + 2)
+                        .and_then(|c| u8::from_str_radix(c, 16).ok())
+                };
+                match (digits.len(), channel(0), channel(2), channel(4)) {
+                    (6, Some(r), Some(g), Some(b)) => Ok(Colour(r, g, b)),
+                    _ => Err(format!("`{}` isn't a colour like `#367da2`", hex)),
+                }
+            }
+        }
+    }
+}
+
+impl From<Colour> for String {
+    fn from(c: Colour) -> Self {
+        c.to_string()
+    }
+}
+
+impl fmt::Display for Colour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+impl From<Colour> for style::Color {
+    fn from(c: Colour) -> Self {
+        style::Color::Rgb(c.0, c.1, c.2)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "snake_case")]
+pub struct Colours {
+    /// The name at the top of both documents.
+    pub name: Colour,
+    /// Section titles, job and degree lines, and project titles.
+    pub heading: Colour,
+    /// Body text.
+    pub text: Colour,
+    /// The resume's contact line.
+    pub muted: Colour,
+    /// The cover letter's small capital labels.
+    pub accent: Colour,
+}
+
+impl Default for Colours {
+    fn default() -> Self {
+        Self {
+            name: Colour(26, 92, 113),
+            heading: Colour(54, 125, 162),
+            text: Colour(50, 50, 50),
+            muted: Colour(96, 96, 96),
+            accent: Colour(129, 173, 187),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "snake_case")]
+pub struct FontSizes {
+    pub name: u8,
+    pub letter_name: u8,
+    pub section: u8,
+    /// Job, degree and project title lines.
+    pub entry: u8,
+    pub body: u8,
+    pub letter_body: u8,
+    pub contact: u8,
+    /// The cover letter's labels and contact line.
+    pub label: u8,
+}
+
+impl Default for FontSizes {
+    fn default() -> Self {
+        Self {
+            name: 27,
+            letter_name: 32,
+            section: 12,
+            entry: 10,
+            body: 9,
+            letter_body: 10,
+            contact: 10,
+            label: 8,
+        }
+    }
+}
+
+/// Page margins in millimetres.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "snake_case")]
+pub struct Spacing {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+impl Spacing {
+    pub const fn trbl(top: f64, right: f64, bottom: f64, left: f64) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+}
+
+impl Spacing {
+    fn widen(self, ratio: f64) -> Self {
+        Self {
+            left: self.left * ratio,
+            right: self.right * ratio,
+            ..self
+        }
+    }
+}
+
+impl Default for Spacing {
+    fn default() -> Self {
+        Self::trbl(0.0, 0.0, 0.0, 0.0)
+    }
+}
+
+impl From<Spacing> for Margins {
+    fn from(s: Spacing) -> Self {
+        Margins::trbl(s.top, s.right, s.bottom, s.left)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "snake_case")]
+pub struct Layout {
+    /// Around the resume's name and contact line.
+    pub header: Spacing,
+    /// Around each resume section.
+    pub content: Spacing,
+    /// Around the cover letter's heading.
+    pub letter_header: Spacing,
+    /// Around the cover letter's body.
+    pub letter: Spacing,
+    pub line_spacing: f64,
+    pub letter_line_spacing: f64,
+    /// How many projects sit side by side in the projects grid.
+    pub project_columns: usize,
+    /// Around each project in the projects grid.
+    pub project_cell: Spacing,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            header: Spacing::trbl(0.0, 12.0, 0.0, 12.0),
+            content: Spacing::trbl(0.0, 12.0, 0.0, 32.0),
+            letter_header: Spacing::trbl(4.0, 44.0, 0.0, 44.0),
+            letter: Spacing::trbl(0.0, 34.5, 0.0, 34.5),
+            line_spacing: 1.2,
+            letter_line_spacing: 1.3,
+            project_columns: 3,
+            project_cell: Spacing::trbl(0.0, 2.0, 4.0, 0.0),
+        }
+    }
+}
+
+/// The colours, font sizes and margins shared by every builder. Fields left
+/// out of a theme file fall back to the `classic` theme.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "snake_case")]
+pub struct Theme {
+    pub colours: Colours,
+    pub font_sizes: FontSizes,
+    pub layout: Layout,
+}
+
+impl Theme {
+    pub const BUILTIN: [&'static str; 4] = ["classic", "slate", "forest", "mono"];
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        let classic = Theme::default();
+        match name.to_ascii_lowercase().as_str() {
+            "classic" => Some(classic),
+            "slate" => Some(Theme {
+                colours: Colours {
+                    name: Colour(33, 43, 66),
+                    heading: Colour(70, 90, 126),
+                    text: Colour(45, 45, 52),
+                    muted: Colour(100, 104, 114),
+                    accent: Colour(140, 150, 170),
+                },
+                ..classic
+            }),
+            "forest" => Some(Theme {
+                colours: Colours {
+                    name: Colour(34, 79, 52),
+                    heading: Colour(58, 118, 80),
+                    text: Colour(44, 48, 44),
+                    muted: Colour(96, 104, 96),
+                    accent: Colour(134, 170, 140),
+                },
+                ..classic
+            }),
+            "mono" => Some(Theme {
+                colours: Colours {
+                    name: Colour(0, 0, 0),
+                    heading: Colour(20, 20, 20),
+                    text: Colour(30, 30, 30),
+                    muted: Colour(80, 80, 80),
+                    accent: Colour(110, 110, 110),
+                },
+                ..classic
+            }),
+            _ => None,
+        }
+    }
+
+    /// Scales the horizontal margins for a page `ratio` times as wide as
+    /// Letter paper, which the themes are designed for.
+    pub fn fit_width(mut self, ratio: f64) -> Self {
+        let layout = &mut self.layout;
+        layout.header = layout.header.widen(ratio);
+        layout.content = layout.content.widen(ratio);
+        layout.letter_header = layout.letter_header.widen(ratio);
+        layout.letter = layout.letter.widen(ratio);
+        self
+    }
+
+    /// Loads a built-in theme by name, or a YAML theme file by path.
+    pub fn load(name_or_path: &str) -> Result<Self, String> {
+        if let Some(theme) = Theme::builtin(name_or_path) {
+            return Ok(theme);
+        }
+        let path: PathBuf = config::expand_home(name_or_path.as_ref());
+        if !path.exists() {
+            return Err(format!(
+                "`{}` isn't a built-in theme ({}) or a theme file",
+                name_or_path,
+                Theme::BUILTIN.join(", ")
+            ));
+        }
+        let source = fs::read_to_string(&path)
+            .map_err(|e| format!("unable to read {}: {}", path.display(), e))?;
+        serde_yaml::from_str(&source)
+            .map_err(|e| format!("invalid theme {}: {}", path.display(), e))
+    }
+}
+
+/// Where the theme comes from in the config: a built-in name or theme file
+/// path, or the theme itself.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ThemeSetting {
+    Named(String),
+    Inline(Theme),
+}
+
+// Written by hand rather than `#[serde(untagged)]` so that a typo in an inline
+// theme reports the offending field instead of "did not match any variant".
+impl<'de> Deserialize<'de> for ThemeSetting {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SettingVisitor;
+
+        impl<'de> Visitor<'de> for SettingVisitor {
+            type Value = ThemeSetting;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a theme name, a theme file path, or a theme")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(ThemeSetting::Named(v.to_string()))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                Theme::deserialize(de::value::MapAccessDeserializer::new(map))
+                    .map(ThemeSetting::Inline)
+            }
+        }
+
+        deserializer.deserialize_any(SettingVisitor)
+    }
+}
+
+impl ThemeSetting {
+    pub fn resolve(&self) -> Result<Theme, String> {
+        match self {
+            ThemeSetting::Named(name) => Theme::load(name),
+            ThemeSetting::Inline(theme) => Ok(*theme),
+        }
+    }
+}
+
+/// Picks the theme for a build: `--theme` wins over the config's `theme`,
+/// which wins over `classic`.
+pub fn select(cli: Option<&str>, setting: Option<&ThemeSetting>) -> Result<Theme, String> {
+    match (cli, setting) {
+        (Some(name), _) => Theme::load(name),
+        (None, Some(setting)) => setting.resolve(),
+        (None, None) => Ok(Theme::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_hex_and_rgb_colours() {
+        let hex: Colour = serde_yaml::from_str("\"#367da2\"").unwrap();
+        assert_eq!(hex, Colour(54, 125, 162));
+        let bare: Colour = serde_yaml::from_str("\"1A5C71\"").unwrap();
+        assert_eq!(bare, Colour(26, 92, 113));
+        let rgb: Colour = serde_yaml::from_str("[54, 125, 162]").unwrap();
+        assert_eq!(rgb, hex);
+        assert_eq!(hex.to_string(), "#367da2");
+    }
+
+    #[test]
+    fn rejects_bad_colours() {
+        for bad in [
+            "\"#367da\"",
+            "\"#367dzz\"",
+            "\"blue\"",
+            "[1, 2]",
+            "[1, 2, 300]",
+        ] {
+            assert!(
+                serde_yaml::from_str::<Colour>(bad).is_err(),
+                "{} was accepted",
+                bad
+            );
+        }
+        let err = serde_yaml::from_str::<Colour>("\"#12345g\"").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`#12345g` isn't a colour like `#367da2`"));
+    }
+
+    #[test]
+    fn fills_in_a_partial_theme_from_classic() {
+        let theme: Theme = serde_yaml::from_str("colours:\n  name: \"#000000\"\n").unwrap();
+        assert_eq!(theme.colours.name, Colour(0, 0, 0));
+        assert_eq!(theme.colours.heading, Theme::default().colours.heading);
+        assert_eq!(theme.layout, Layout::default());
+    }
+
+    #[test]
+    fn reports_a_misspelt_field() {
+        let err = serde_yaml::from_str::<ThemeSetting>("colours:\n  heding: \"#000000\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown field `heding`"), "{}", err);
+        let err = serde_yaml::from_str::<ThemeSetting>("layout:\n  content: { lft: 3 }\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown field `lft`"), "{}", err);
+    }
+
+    #[test]
+    fn select_prefers_the_cli_then_the_config() {
+        let slate = Theme::builtin("slate").unwrap();
+        let forest = Theme::builtin("forest").unwrap();
+        let named = ThemeSetting::Named("forest".to_string());
+        let inline = ThemeSetting::Inline(slate);
+
+        assert_eq!(select(Some("mono"), Some(&named)), Theme::load("mono"));
+        assert_eq!(select(None, Some(&named)), Ok(forest));
+        assert_eq!(select(None, Some(&inline)), Ok(slate));
+        assert_eq!(select(None, None), Ok(Theme::default()));
+        assert!(select(Some("no-such-theme"), Some(&named)).is_err());
+    }
+}
@@ -1,8 +1,11 @@
+use crate::builders::links::LinkLine;
+use crate::builders::theme::Theme;
 use crate::helpers::capitalize;
-use genpdf::{elements::*, render, style, Alignment, Element, Margins, Position};
+use genpdf::{elements::*, style, Alignment, Element};
 use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
 
-pub fn section_title(section: &String) -> PaddedElement<LinearLayout> {
+pub fn section_title(theme: &Theme, section: &str) -> PaddedElement<LinearLayout> {
     let mut layout = LinearLayout::vertical();
 
     let mut para = Paragraph::default();
@@ -12,14 +15,159 @@ pub fn section_title(section: &String) -> PaddedElement<LinearLayout> {
     layout.push(
         para.styled(
             style::Style::new()
-                .with_color(style::Color::Rgb(54, 125, 162))
-                .with_font_size(12)
+                .with_color(theme.colours.heading.into())
+                .with_font_size(theme.font_sizes.section)
                 .bold()
                 .with_line_spacing(1.0),
         ),
     );
-    let margins = Margins::trbl(0, 12, 0, 32);
-    PaddedElement::new(layout, margins)
+    PaddedElement::new(layout, theme.layout.content)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContactKind {
+    Phone,
+    Email,
+    Website,
+    Linkedin,
+    Github,
+    Custom,
+}
+
+impl ContactKind {
+    pub const NAMES: [&'static str; 6] =
+        ["phone", "email", "website", "linkedin", "github", "custom"];
+}
+
+impl fmt::Display for ContactKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ContactKind::Phone => "phone",
+            ContactKind::Email => "email",
+            ContactKind::Website => "website",
+            ContactKind::Linkedin => "linkedin",
+            ContactKind::Github => "github",
+            ContactKind::Custom => "custom",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for ContactKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "phone" => Ok(ContactKind::Phone),
+            "email" => Ok(ContactKind::Email),
+            "website" => Ok(ContactKind::Website),
+            "linkedin" => Ok(ContactKind::Linkedin),
+            "github" => Ok(ContactKind::Github),
+            "custom" => Ok(ContactKind::Custom),
+            other => Err(format!(
+                "unknown contact type `{}` (expected one of: {})",
+                other,
+                ContactKind::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ContactItem {
+    pub kind: ContactKind,
+    /// The text shown on the documents, e.g. `github.com/octocat`.
+    pub label: String,
+    pub url: Option<String>,
+}
+
+impl ContactItem {
+    /// Where the item links to: its `url`, or one worked out from the label.
+    pub fn href(&self) -> Option<String> {
+        if let Some(url) = &self.url {
+            return Some(url.clone());
+        }
+        let label = self.label.trim();
+        match self.kind {
+            ContactKind::Email => Some(format!("mailto:{}", label)),
+            ContactKind::Phone => {
+                let number: String = label
+                    .chars()
+                    .filter(|c| c.is_ascii_digit() || *c == '+')
+                    .collect();
+                (!number.is_empty()).then(|| format!("tel:{}", number))
+            }
+            ContactKind::Website | ContactKind::Linkedin | ContactKind::Github => {
+                if label.contains("://") {
+                    Some(label.to_string())
+                } else {
+                    Some(format!("https://{}", label))
+                }
+            }
+            ContactKind::Custom => None,
+        }
+    }
+}
+
+/// The contact items as a line of links, for the documents.
+pub fn contact_links(items: &[ContactItem]) -> LinkLine {
+    let mut line = LinkLine::default();
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            line.push_separator(" | ");
+        }
+        line.push(item.label.clone(), item.href());
+    }
+    line
+}
+
+/// Joins the contact items into the single line shown under the name.
+pub fn contact_line(items: &[ContactItem]) -> String {
+    items
+        .iter()
+        .map(|item| item.label.as_str())
+        .collect::<Vec<&str>>()
+        .join(" | ")
+}
+
+/// The spans of `text` between its `**` markers, each with whether it is
+/// emphasised. Every format maps these to its own bold.
+pub fn marked_spans(text: &str) -> impl Iterator<Item = (&str, bool)> {
+    text.split("**")
+        .enumerate()
+        .filter(|(_, span)| !span.is_empty())
+        .map(|(i, span)| (span, i % 2 == 1))
+}
+
+/// Pushes `text` onto `para`, rendering any `**emphasised**` spans in bold.
+pub fn push_marked(para: &mut Paragraph, text: &str) {
+    for (span, bold) in marked_spans(text) {
+        if bold {
+            para.push_styled(span.to_string(), style::Style::new().bold());
+        } else {
+            para.push(span.to_string());
+        }
+    }
+}
+
+pub fn summary_paragraph(theme: &Theme, summary: &str) -> PaddedElement<LinearLayout> {
+    let mut layout = LinearLayout::vertical();
+
+    let mut para = Paragraph::default();
+    para.set_alignment(Alignment::Left);
+    push_marked(&mut para, summary);
+
+    layout.push(
+        para.styled(
+            style::Style::new()
+                .with_color(theme.colours.text.into())
+                .with_font_size(theme.font_sizes.body)
+                .with_line_spacing(1.0),
+        ),
+    );
+    PaddedElement::new(layout, theme.layout.content)
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -29,7 +177,7 @@ pub struct Skill {
     pub skills: Vec<String>,
 }
 
-pub fn subskill(s: Skill, mut layout: LinearLayout) -> LinearLayout {
+pub fn subskill(theme: &Theme, s: Skill, mut layout: LinearLayout) -> LinearLayout {
     let mut para = Paragraph::default();
     para.set_alignment(Alignment::Left);
     para.push_styled(s.section, style::Style::new().bold());
@@ -38,22 +186,20 @@ pub fn subskill(s: Skill, mut layout: LinearLayout) -> LinearLayout {
     layout.push(
         para.styled(
             style::Style::new()
-                .with_color(style::Color::Rgb(50, 50, 50))
-                .with_font_size(9)
+                .with_color(theme.colours.text.into())
+                .with_font_size(theme.font_sizes.body)
                 .with_line_spacing(1.0),
         ),
     );
     layout
 }
 
-pub fn skills(skills: Vec<Skill>) -> PaddedElement<LinearLayout> {
+pub fn skills(theme: &Theme, skills: Vec<Skill>) -> PaddedElement<LinearLayout> {
     let mut layout = LinearLayout::vertical();
     for skill in skills {
-        layout = subskill(skill, layout);
+        layout = subskill(theme, skill, layout);
     }
-    let margins = Margins::trbl(0, 12, 0, 32);
-    let ele = PaddedElement::new(layout, margins);
-    ele
+    PaddedElement::new(layout, theme.layout.content)
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -67,7 +213,11 @@ pub struct WorkExperience {
     pub highlights: Vec<String>,
 }
 
-pub fn work_experience(expr: WorkExperience, mut layout: LinearLayout) -> LinearLayout {
+pub fn work_experience(
+    theme: &Theme,
+    expr: WorkExperience,
+    mut layout: LinearLayout,
+) -> LinearLayout {
     let mut para = Paragraph::default();
     para.set_alignment(Alignment::Left);
     para.push_styled(
@@ -81,8 +231,8 @@ pub fn work_experience(expr: WorkExperience, mut layout: LinearLayout) -> Linear
     layout.push(
         para.styled(
             style::Style::new()
-                .with_color(style::Color::Rgb(54, 125, 162))
-                .with_font_size(10)
+                .with_color(theme.colours.heading.into())
+                .with_font_size(theme.font_sizes.entry)
                 .with_line_spacing(1.0),
         ),
     );
@@ -90,30 +240,30 @@ pub fn work_experience(expr: WorkExperience, mut layout: LinearLayout) -> Linear
     let mut list = UnorderedList::with_bullet("•");
 
     for item in expr.highlights {
-        list.push(Paragraph::new(item));
+        let mut para = Paragraph::default();
+        push_marked(&mut para, &item);
+        list.push(para);
     }
 
     layout.push(
         list.styled(
             style::Style::new()
-                .with_color(style::Color::Rgb(50, 50, 50))
-                .with_font_size(9)
+                .with_color(theme.colours.text.into())
+                .with_font_size(theme.font_sizes.body)
                 .with_line_spacing(1.0),
         ),
     );
     layout
 }
 
-pub fn employment(jobs: Vec<WorkExperience>) -> PaddedElement<LinearLayout> {
+pub fn employment(theme: &Theme, jobs: Vec<WorkExperience>) -> PaddedElement<LinearLayout> {
     let mut layout = LinearLayout::vertical();
 
     for job in jobs {
-        layout = work_experience(job, layout);
+        layout = work_experience(theme, job, layout);
     }
 
-    let margins = Margins::trbl(0, 12, 0, 32);
-    let ele = PaddedElement::new(layout, margins);
-    ele
+    PaddedElement::new(layout, theme.layout.content)
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -126,7 +276,7 @@ pub struct Degree {
     pub description: String,
 }
 
-pub fn degree(deg: Degree, mut layout: LinearLayout) -> LinearLayout {
+pub fn degree(theme: &Theme, deg: Degree, mut layout: LinearLayout) -> LinearLayout {
     let mut para = Paragraph::default();
     para.set_alignment(Alignment::Left);
     para.push_styled(
@@ -137,8 +287,8 @@ pub fn degree(deg: Degree, mut layout: LinearLayout) -> LinearLayout {
     layout.push(
         para.styled(
             style::Style::new()
-                .with_color(style::Color::Rgb(54, 125, 162))
-                .with_font_size(10)
+                .with_color(theme.colours.heading.into())
+                .with_font_size(theme.font_sizes.entry)
                 .with_line_spacing(1.0),
         ),
     );
@@ -149,24 +299,22 @@ pub fn degree(deg: Degree, mut layout: LinearLayout) -> LinearLayout {
     layout.push(
         para.styled(
             style::Style::new()
-                .with_color(style::Color::Rgb(50, 50, 50))
-                .with_font_size(9)
+                .with_color(theme.colours.text.into())
+                .with_font_size(theme.font_sizes.body)
                 .with_line_spacing(1.0),
         ),
     );
     layout
 }
 
-pub fn education(degrees: Vec<Degree>) -> PaddedElement<LinearLayout> {
+pub fn education(theme: &Theme, degrees: Vec<Degree>) -> PaddedElement<LinearLayout> {
     let mut layout = LinearLayout::vertical();
 
     for deg in degrees {
-        layout = degree(deg, layout);
+        layout = degree(theme, deg, layout);
     }
 
-    let margins = Margins::trbl(0, 12, 0, 32);
-    let ele = PaddedElement::new(layout, margins);
-    ele
+    PaddedElement::new(layout, theme.layout.content)
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -176,60 +324,98 @@ pub struct Project {
     pub title: String,
     pub organization: String,
     pub year: String,
+    /// A line or two about the project, shown under the title.
+    pub description: Option<String>,
+    pub url: Option<String>,
 }
 
-pub fn project_cell(proj: &Project, mut row: Vec<Box<dyn Element>>) -> Vec<Box<dyn Element>> {
+pub fn project_cell(theme: &Theme, proj: &Project) -> PaddedElement<LinearLayout> {
     let mut layout = LinearLayout::vertical();
     let mut para = Paragraph::default();
-    if proj.nickname.is_some() {
+    if let Some(nickname) = &proj.nickname {
         para.push_styled(
-            format!("{} - ", proj.nickname.clone().unwrap()),
+            format!("{} - ", nickname),
             style::Style::new()
                 .italic()
-                .with_font_size(10)
+                .with_font_size(theme.font_sizes.entry)
                 .with_line_spacing(1.0)
-                .with_color(style::Color::Rgb(54, 125, 162)),
+                .with_color(theme.colours.heading.into()),
         )
     }
     para.push_styled(
         proj.title.clone(),
         style::Style::new()
-            .with_font_size(10)
+            .with_font_size(theme.font_sizes.entry)
             .with_line_spacing(1.0)
-            .with_color(style::Color::Rgb(54, 125, 162)),
+            .with_color(theme.colours.heading.into()),
     );
     layout.push(para);
     let mut para = Paragraph::default();
     para.push_styled(
         format!("{} - {}", proj.organization, proj.year),
         style::Style::new()
-            .with_color(style::Color::Rgb(50, 50, 50))
-            .with_font_size(9)
+            .with_color(theme.colours.text.into())
+            .with_font_size(theme.font_sizes.body)
             .with_line_spacing(1.0),
     );
     layout.push(para);
-    let margins = Margins::trbl(0, 0, 4, 0);
-    let ele = PaddedElement::new(layout, margins);
-    row.push(Box::new(ele));
-    row
-}
-
-pub fn projects(projs: Vec<Project>) -> PaddedElement<TableLayout> {
-    let mut table = TableLayout::new(vec![1, 1, 1]);
-    for i in 0..projs.len() % 3 {
-        let mut row: Vec<Box<dyn Element>> = Vec::new();
-        if i + 3 < projs.len() {
-            for proj in &projs[i..i + 3] {
-                row = project_cell(proj, row);
-            }
-        } else {
-            for proj in &projs[i..] {
-                row = project_cell(proj, row);
-            }
-        }
-        table.push_row(row).ok();
+    if let Some(description) = &proj.description {
+        let mut para = Paragraph::default();
+        push_marked(&mut para, description);
+        layout.push(
+            para.styled(
+                style::Style::new()
+                    .with_color(theme.colours.text.into())
+                    .with_font_size(theme.font_sizes.body)
+                    .with_line_spacing(1.0),
+            ),
+        );
+    }
+    if let Some(url) = &proj.url {
+        let mut line = LinkLine::default();
+        line.push(url.clone(), Some(url.clone()));
+        layout.push(
+            line.styled(
+                style::Style::new()
+                    .with_color(theme.colours.muted.into())
+                    .with_font_size(theme.font_sizes.body)
+                    .with_line_spacing(1.0),
+            ),
+        );
+    }
+    PaddedElement::new(layout, theme.layout.project_cell)
+}
+
+/// Splits `items` into rows of `columns` cells, left to right. The last row is
+/// padded with `None` so that every row is the same width.
+pub fn grid<T>(items: &[T], columns: usize) -> Vec<Vec<Option<&T>>> {
+    let columns = columns.max(1);
+    items
+        .chunks(columns)
+        .map(|chunk| {
+            let mut row: Vec<Option<&T>> = chunk.iter().map(Some).collect();
+            row.resize(columns, None);
+            row
+        })
+        .collect()
+}
+
+pub fn projects(theme: &Theme, projs: Vec<Project>) -> PaddedElement<TableLayout> {
+    let columns = theme.layout.project_columns.max(1);
+    let mut table = TableLayout::new(vec![1; columns]);
+    for cells in grid(&projs, columns) {
+        let row: Vec<Box<dyn Element>> = cells
+            .into_iter()
+            .map(|cell| -> Box<dyn Element> {
+                match cell {
+                    Some(proj) => Box::new(project_cell(theme, proj)),
+                    None => Box::new(LinearLayout::vertical()),
+                }
+            })
+            .collect();
+        table
+            .push_row(row)
+            .expect("grid rows always have one cell per column");
     }
-    let margins = Margins::trbl(0, 12, 0, 32);
-    let ele = PaddedElement::new(table, margins);
-    ele
+    PaddedElement::new(table, theme.layout.content)
 }
@@ -1,13 +1,17 @@
-use crate::config::{load_settings, write_settings};
+use crate::builders::utils::{ContactItem, ContactKind};
+use crate::config::{file_settings, save_settings, write_settings};
 use clap::{
     error::{ContextKind, ContextValue, Error as ClapError, ErrorKind, Result},
     ArgMatches, Command,
 };
+use color_print::cprintln;
 
+#[allow(clippy::needless_return)]
 pub fn config(cmd: Command, matches: &ArgMatches) -> Result<(), ClapError> {
     match matches.subcommand() {
         Some(("name", sub_matches)) => crate::commands::name(cmd.clone(), sub_matches),
         Some(("api", sub_matches)) => crate::commands::api(cmd.clone(), sub_matches),
+        Some(("contact", sub_matches)) => crate::commands::contact(cmd.clone(), sub_matches),
         Some((sub, _)) => return Err(crate::helpers::unable_to_parse_subcommand(cmd, Some(sub))),
         None => return Err(crate::helpers::unable_to_parse_subcommand(cmd, None)),
     }
@@ -28,12 +32,11 @@ pub fn name(cmd: Command, matches: &ArgMatches) -> Result<(), ClapError> {
             return Err(err);
         }
     };
-    let existing = load_settings().expect("Unable to load config");
-    write_settings(&cmd, Some(existing), Some(name), None)
+    write_settings(&cmd, Some(name), None)
 }
 
 pub fn api(cmd: Command, matches: &ArgMatches) -> Result<(), ClapError> {
-    let api: String = match matches.get_one::<String>("api") {
+    let api: String = match matches.get_one::<String>("api-key") {
         Some(a) => a.to_string(),
         None => {
             let mut err = ClapError::new(ErrorKind::MissingRequiredArgument).with_cmd(&cmd);
@@ -47,6 +50,110 @@ pub fn api(cmd: Command, matches: &ArgMatches) -> Result<(), ClapError> {
             return Err(err);
         }
     };
-    let existing = load_settings().expect("Unable to load config");
-    write_settings(&cmd, Some(existing), None, Some(api))
+    write_settings(&cmd, None, Some(api))
+}
+
+pub fn contact(cmd: Command, matches: &ArgMatches) -> Result<(), ClapError> {
+    let mut existing = match file_settings() {
+        Ok(existing) => existing,
+        Err(e) => {
+            let err = ClapError::raw(
+                ErrorKind::Io,
+                format!("Unable to read ~/.cv.config.yaml: {}\n", e),
+            )
+            .with_cmd(&cmd);
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
+        }
+    };
+    match matches.subcommand() {
+        Some(("add", sub_matches)) => {
+            let kind = match sub_matches
+                .get_one::<String>("kind")
+                .map(|k| k.parse::<ContactKind>())
+            {
+                Some(Ok(kind)) => kind,
+                _ => {
+                    let mut err = ClapError::new(ErrorKind::InvalidValue).with_cmd(&cmd);
+                    err.insert(ContextKind::InvalidArg, ContextValue::None);
+                    err.insert(
+                        ContextKind::ValidValue,
+                        ContextValue::Strings(
+                            ContactKind::NAMES.iter().map(|n| n.to_string()).collect(),
+                        ),
+                    );
+                    let e = err.render();
+                    print!("{}", e.ansi());
+                    return Err(err);
+                }
+            };
+            let label: String = match sub_matches.get_one::<String>("label") {
+                Some(l) => l.to_string(),
+                None => {
+                    let mut err = ClapError::new(ErrorKind::MissingRequiredArgument).with_cmd(&cmd);
+                    err.insert(ContextKind::InvalidArg, ContextValue::None);
+                    err.insert(
+                        ContextKind::SuggestedValue,
+                        ContextValue::String("`github.com/octocat'".to_string()),
+                    );
+                    let e = err.render();
+                    print!("{}", e.ansi());
+                    return Err(err);
+                }
+            };
+            let url: Option<String> = sub_matches.get_one::<String>("url").cloned();
+            existing.contact.push(ContactItem { kind, label, url });
+            save_settings(&cmd, &existing)
+        }
+        Some(("remove", sub_matches)) => {
+            let item: String = sub_matches
+                .get_one::<String>("item")
+                .cloned()
+                .unwrap_or_default();
+            let index = match item.parse::<usize>() {
+                Ok(n) if n >= 1 && n <= existing.contact.len() => Some(n - 1),
+                _ => existing.contact.iter().position(|c| c.label == item),
+            };
+            match index {
+                Some(i) => {
+                    existing.contact.remove(i);
+                    save_settings(&cmd, &existing)
+                }
+                None => {
+                    let err = ClapError::raw(
+                        ErrorKind::InvalidValue,
+                        format!(
+                            "No contact item matches `{}`; run `cv config contact list` to see them\n",
+                            item
+                        ),
+                    )
+                    .with_cmd(&cmd);
+                    let e = err.render();
+                    print!("{}", e.ansi());
+                    Err(err)
+                }
+            }
+        }
+        Some(("list", _)) => {
+            if existing.contact.is_empty() {
+                cprintln!("<dim>No contact items have been set</>");
+            }
+            for (i, item) in existing.contact.iter().enumerate() {
+                match &item.url {
+                    Some(url) => cprintln!(
+                        "<bold>{}.</> <green>{}</> {} <dim>({})</>",
+                        i + 1,
+                        item.kind,
+                        item.label,
+                        url
+                    ),
+                    None => cprintln!("<bold>{}.</> <green>{}</> {}", i + 1, item.kind, item.label),
+                }
+            }
+            Ok(())
+        }
+        Some((sub, _)) => Err(crate::helpers::unable_to_parse_subcommand(cmd, Some(sub))),
+        None => Err(crate::helpers::unable_to_parse_subcommand(cmd, None)),
+    }
 }
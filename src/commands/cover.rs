@@ -1,48 +1,29 @@
-use crate::config;
+use crate::builders::template::{paragraphs, Context, Template};
+use crate::builders::{format::Format, metadata::Metadata, Settings};
+use crate::config::{self, ConfigFile};
+use crate::generate::{self, CoverLetterRequest, Provider};
 use clap::{
     error::{ContextKind, ContextValue, Error as ClapError, ErrorKind, Result},
     ArgMatches, Command,
 };
-use std::path::PathBuf;
+use std::{fs, path::PathBuf};
 
-pub fn coverletter(cmd: Command, matches: &ArgMatches) -> Result<(), ClapError> {
-    let name: String = match matches.get_one::<String>("name") {
-        Some(namearg) => namearg.to_string(),
-        None => match config::load_settings() {
-            Ok(conf) => match conf.name {
-                Some(name) => name,
-                None => {
-                    let mut err = ClapError::new(ErrorKind::MissingRequiredArgument).with_cmd(&cmd);
-                    err.insert(
-                        ContextKind::Usage,
-                        ContextValue::StyledStr(crate::CONFIG_NAME.into()),
-                    );
-                    let e = err.render();
-                    print!("{}", e.ansi());
-                    return Err(err);
-                }
-            },
-            Err(_) => {
-                let mut err = ClapError::new(ErrorKind::MissingRequiredArgument).with_cmd(&cmd);
-                err.insert(ContextKind::InvalidArg, ContextValue::None);
-                err.insert(
-                    ContextKind::SuggestedArg,
-                    ContextValue::String("--name".to_string()),
-                );
-                err.insert(
-                    ContextKind::SuggestedValue,
-                    ContextValue::String("`Johnny Appleseed'".to_string()),
-                );
-                err.insert(
-                    ContextKind::Usage,
-                    ContextValue::StyledStr(crate::CONFIG_NAME.into()),
-                );
-                let e = err.render();
-                print!("{}", e.ansi());
-                return Err(err);
-            }
-        },
+pub async fn coverletter(cmd: Command, matches: &ArgMatches) -> Result<(), ClapError> {
+    let conf = match config::load_settings() {
+        Ok(conf) => conf,
+        Err(e) => {
+            let err = ClapError::raw(
+                ErrorKind::InvalidValue,
+                format!("Unable to load ~/.cv.config.yaml: {}\n", e),
+            )
+            .with_cmd(&cmd);
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
+        }
     };
+
+    let name = super::resolve_name(&cmd, matches, &conf)?;
     let company: String = match matches.get_one::<String>("company") {
         Some(co) => co.to_owned(),
         None => {
@@ -63,6 +44,7 @@ pub fn coverletter(cmd: Command, matches: &ArgMatches) -> Result<(), ClapError>
     };
     let location: Option<String> = matches.get_one::<String>("location").cloned();
     let position: Option<String> = matches.get_one::<String>("position").cloned();
+    let hiring_manager: Option<String> = matches.get_one::<String>("hiring-manager").cloned();
     let output: PathBuf = match matches.get_one::<PathBuf>("output") {
         Some(output) => output.to_owned(),
         None => {
@@ -78,17 +60,156 @@ pub fn coverletter(cmd: Command, matches: &ArgMatches) -> Result<(), ClapError>
         }
     };
 
-    match config::load_settings() {
-        Ok(conf) => {
-            if (conf.name.is_some() && conf.name.as_ref().unwrap() != &name)
-                || conf.name.as_ref().is_none()
-            {
-                let _ = config::write_settings(&cmd, Some(conf), Some(name.clone()), None)?;
-            }
+    let job_description = super::job_description(&cmd, matches).await?;
+    let body = letter_body(
+        &cmd,
+        matches,
+        &conf,
+        &name,
+        &company,
+        position.as_deref(),
+        location.as_deref(),
+        hiring_manager.as_deref(),
+        job_description.as_deref(),
+    )
+    .await?;
+
+    let mut settings = match Settings::load(&conf, matches) {
+        Ok(settings) => settings,
+        Err(e) => {
+            let err = ClapError::raw(ErrorKind::InvalidValue, format!("{}\n", e)).with_cmd(&cmd);
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
         }
-        Err(_) => (),
     };
 
-    crate::builders::coverletter::build(name, company, location, position, output);
-    Ok(())
+    if settings.format == Format::Pdf {
+        settings.signature = super::signature(&conf);
+    }
+    settings.metadata =
+        Metadata::cover_letter(&name, &company, position.as_deref(), conf.resume.as_ref())
+            .with_title(matches.get_one::<String>("title").map(String::as_str));
+
+    let contact = conf.contact.clone();
+
+    if conf.name.as_ref() != Some(&name) {
+        config::write_settings(&cmd, Some(name.clone()), None)?;
+    }
+
+    super::saved(
+        &cmd,
+        &settings.format.path(output.clone()),
+        crate::builders::coverletter::build(
+            name,
+            company,
+            location,
+            hiring_manager,
+            body,
+            contact,
+            settings,
+            output,
+        ),
+    )
+}
+
+/// Has the configured model write the letter body with `--generate`, and
+/// fills in the template otherwise.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn letter_body(
+    cmd: &Command,
+    matches: &ArgMatches,
+    conf: &ConfigFile,
+    name: &str,
+    company: &str,
+    position: Option<&str>,
+    location: Option<&str>,
+    hiring_manager: Option<&str>,
+    job_description: Option<&str>,
+) -> Result<Vec<String>, ClapError> {
+    if matches.get_flag("generate") {
+        if conf.provider.unwrap_or_default() == Provider::Openai && conf.api_key.is_none() {
+            let mut err = ClapError::new(ErrorKind::MissingRequiredArgument).with_cmd(cmd);
+            err.insert(
+                ContextKind::Usage,
+                ContextValue::StyledStr(crate::CONFIG_API.into()),
+            );
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
+        }
+        let resume = super::load_resume(cmd, matches, conf)?;
+        let request = CoverLetterRequest {
+            name,
+            company,
+            position,
+            location,
+            hiring_manager,
+            job_description,
+            resume: &resume,
+        };
+        let generated = match generate::from_config(conf) {
+            Ok(generator) => generate::cover_letter_body(generator.as_ref(), &request).await,
+            Err(e) => Err(e),
+        };
+        match generated {
+            Ok(body) => Ok(body),
+            Err(e) => {
+                let err = ClapError::raw(
+                    ErrorKind::InvalidValue,
+                    format!("Unable to generate the cover letter: {}\n", e),
+                )
+                .with_cmd(cmd);
+                let e = err.render();
+                print!("{}", e.ansi());
+                Err(err)
+            }
+        }
+    } else {
+        let template_path: Option<PathBuf> =
+            matches.get_one::<PathBuf>("template").cloned().or_else(|| {
+                conf.cover_letter
+                    .as_ref()
+                    .and_then(|c| c.template.as_deref())
+                    .map(config::expand_home)
+            });
+
+        let (source, template) = match template_path {
+            Some(path) => (
+                path.display().to_string(),
+                fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|source| Template::parse(&source)),
+            ),
+            None => (
+                "(bundled default)".to_string(),
+                Ok(Template::default_cover_letter()),
+            ),
+        };
+
+        let mut context = Context::new();
+        context.insert("company", Some(company.to_string()));
+        context.insert("position", position.map(str::to_string));
+        context.insert("location", location.map(str::to_string));
+        context.insert("hiring_manager", hiring_manager.map(str::to_string));
+        context.insert("name", Some(name.to_string()));
+        context.insert("date", Some(crate::helpers::today()));
+
+        match template.and_then(|t| t.render(&context)) {
+            Ok(text) => Ok(paragraphs(&text)),
+            Err(e) => {
+                let err = ClapError::raw(
+                    ErrorKind::InvalidValue,
+                    format!(
+                        "Unable to use the cover letter template {}: {}\n",
+                        source, e
+                    ),
+                )
+                .with_cmd(cmd);
+                let e = err.render();
+                print!("{}", e.ansi());
+                Err(err)
+            }
+        }
+    }
 }
@@ -1,7 +1,173 @@
 mod config;
 mod cover;
+mod packet;
 mod resume;
+mod score;
 
 pub use config::*;
 pub use cover::*;
+pub use packet::*;
 pub use resume::*;
+pub use score::*;
+
+use crate::builders::coverletter;
+use crate::builders::pages::{Optimization, Saved};
+use crate::config::{ConfigFile, Resume};
+use crate::job::JobSource;
+use clap::{
+    error::{ContextKind, ContextValue, Error as ClapError, ErrorKind},
+    ArgMatches, Command,
+};
+use color_print::cprintln;
+use image::DynamicImage;
+use std::path::Path;
+
+/// The name from `--name`, or the one saved in the config.
+fn resolve_name(
+    cmd: &Command,
+    matches: &ArgMatches,
+    conf: &ConfigFile,
+) -> Result<String, ClapError> {
+    match matches.get_one::<String>("name") {
+        Some(namearg) => Ok(namearg.to_string()),
+        None => match conf.name.clone() {
+            Some(name) => Ok(name),
+            None => {
+                let mut err = ClapError::new(ErrorKind::MissingRequiredArgument).with_cmd(cmd);
+                err.insert(ContextKind::InvalidArg, ContextValue::None);
+                err.insert(
+                    ContextKind::SuggestedArg,
+                    ContextValue::String("--name".to_string()),
+                );
+                err.insert(
+                    ContextKind::SuggestedValue,
+                    ContextValue::String("`Johnny Appleseed'".to_string()),
+                );
+                err.insert(
+                    ContextKind::Usage,
+                    ContextValue::StyledStr(crate::CONFIG_NAME.into()),
+                );
+                let e = err.render();
+                print!("{}", e.ansi());
+                Err(err)
+            }
+        },
+    }
+}
+
+/// The resume from the config, or the `--variant` of it, checked for missing
+/// fields.
+fn load_resume(
+    cmd: &Command,
+    matches: &ArgMatches,
+    conf: &ConfigFile,
+) -> Result<Resume, ClapError> {
+    let mut resume = match conf.resume.clone() {
+        Some(resume) => resume,
+        None => {
+            let mut err = ClapError::new(ErrorKind::MissingRequiredArgument).with_cmd(cmd);
+            err.insert(
+                ContextKind::Usage,
+                ContextValue::StyledStr(crate::CONFIG_RESUME.into()),
+            );
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
+        }
+    };
+
+    if let Some(variant) = matches.get_one::<String>("variant") {
+        resume = match resume.variant(variant) {
+            Ok(resume) => resume,
+            Err(e) => {
+                let err = ClapError::raw(
+                    ErrorKind::InvalidValue,
+                    format!("Unable to build the resume variant: {}\n", e),
+                )
+                .with_cmd(cmd);
+                let e = err.render();
+                print!("{}", e.ansi());
+                return Err(err);
+            }
+        };
+    }
+
+    if let Err(problems) = resume.validate() {
+        let err = ClapError::raw(
+            ErrorKind::InvalidValue,
+            format!(
+                "The resume block in ~/.cv.config.yaml is incomplete:\n{}\n",
+                problems
+            ),
+        )
+        .with_cmd(cmd);
+        let e = err.render();
+        print!("{}", e.ansi());
+        return Err(err);
+    }
+    Ok(resume)
+}
+
+/// The job posting from `--job-description`, `--job-file` or `--job-url`, if
+/// one was given.
+async fn job_description(cmd: &Command, matches: &ArgMatches) -> Result<Option<String>, ClapError> {
+    match JobSource::from_matches(matches) {
+        Some(source) => match source.load().await {
+            Ok(text) => Ok(Some(text)),
+            Err(e) => {
+                let err = ClapError::raw(
+                    ErrorKind::InvalidValue,
+                    format!("Unable to load the job posting: {}\n", e),
+                )
+                .with_cmd(cmd);
+                let e = err.render();
+                print!("{}", e.ansi());
+                Err(err)
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+/// The signature image named in the config's `cover_letter` block. One that
+/// can't be read is left out with a warning rather than stopping the letter.
+fn signature(conf: &ConfigFile) -> Option<DynamicImage> {
+    let path = crate::config::expand_home(conf.cover_letter.as_ref()?.signature.as_ref()?);
+    match coverletter::signature(&path) {
+        Ok(image) => Some(image),
+        Err(e) => {
+            cprintln!(
+                "<yellow>Couldn't read the signature {}: {}</>",
+                path.display(),
+                e
+            );
+            cprintln!("<dim>Signing with your typed name only</>");
+            None
+        }
+    }
+}
+
+/// Reports where a document was saved, or renders the error from saving it
+/// to `path`.
+fn saved(cmd: &Command, path: &Path, result: Result<Saved, String>) -> Result<(), ClapError> {
+    match result {
+        Ok(saved) => {
+            if let Optimization::Failed(e) = &saved.optimization {
+                cprintln!("<yellow>Couldn't optimise the PDF: {}</>", e);
+                cprintln!("<dim>Wrote it without optimising instead</>");
+            }
+            cprintln!("<green>Saved {}</>", saved.path.display());
+            Ok(())
+        }
+        Err(e) => {
+            let err = ClapError::raw(
+                ErrorKind::Io,
+                format!("Unable to save {}: {}\n", path.display(), e),
+            )
+            .with_cmd(cmd);
+            let e = err.render();
+            print!("{}", e.ansi());
+            Err(err)
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use crate::builders::{format::Format, metadata::Metadata, Settings};
+use crate::config;
+use crate::keywords;
+use clap::{
+    error::{ContextKind, ContextValue, Error as ClapError, ErrorKind, Result},
+    ArgMatches, Command,
+};
+use std::path::PathBuf;
+
+pub async fn packet(cmd: Command, matches: &ArgMatches) -> Result<(), ClapError> {
+    let conf = match config::load_settings() {
+        Ok(conf) => conf,
+        Err(e) => {
+            let err = ClapError::raw(
+                ErrorKind::InvalidValue,
+                format!("Unable to load ~/.cv.config.yaml: {}\n", e),
+            )
+            .with_cmd(&cmd);
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
+        }
+    };
+
+    let name = super::resolve_name(&cmd, matches, &conf)?;
+    let company: String = match matches.get_one::<String>("company") {
+        Some(co) => co.to_owned(),
+        None => {
+            let mut err = ClapError::new(ErrorKind::MissingRequiredArgument).with_cmd(&cmd);
+            err.insert(ContextKind::InvalidArg, ContextValue::None);
+            err.insert(
+                ContextKind::SuggestedArg,
+                ContextValue::String("--company".to_string()),
+            );
+            err.insert(
+                ContextKind::SuggestedValue,
+                ContextValue::String("<COMPANY>".to_string()),
+            );
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
+        }
+    };
+    let position: String = match matches.get_one::<String>("position") {
+        Some(position) => position.to_owned(),
+        None => {
+            let mut err = ClapError::new(ErrorKind::MissingRequiredArgument).with_cmd(&cmd);
+            err.insert(ContextKind::InvalidArg, ContextValue::None);
+            err.insert(
+                ContextKind::SuggestedArg,
+                ContextValue::String("--position".to_string()),
+            );
+            err.insert(
+                ContextKind::SuggestedValue,
+                ContextValue::String("Professional Thinker".to_string()),
+            );
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
+        }
+    };
+    let location: Option<String> = matches.get_one::<String>("location").cloned();
+    let hiring_manager: Option<String> = matches.get_one::<String>("hiring-manager").cloned();
+    let output: PathBuf = match matches.get_one::<PathBuf>("output") {
+        Some(output) => output.to_owned(),
+        None => {
+            let mut err = ClapError::new(ErrorKind::MissingRequiredArgument).with_cmd(&cmd);
+            err.insert(ContextKind::TrailingArg, ContextValue::None);
+            err.insert(
+                ContextKind::SuggestedArg,
+                ContextValue::String("./application.pdf".to_string()),
+            );
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
+        }
+    };
+
+    let mut resume = super::load_resume(&cmd, matches, &conf)?;
+    let job_description = super::job_description(&cmd, matches).await?;
+    let max_highlights: Option<usize> = matches.get_one::<usize>("max-highlights").copied();
+
+    if max_highlights.is_some() || job_description.is_some() {
+        let query = match &job_description {
+            Some(job) => format!("{}\n{}", position, job),
+            None => position.clone(),
+        };
+        keywords::tailor(&mut resume, &query, max_highlights);
+    }
+
+    let body = super::letter_body(
+        &cmd,
+        matches,
+        &conf,
+        &name,
+        &company,
+        Some(&position),
+        location.as_deref(),
+        hiring_manager.as_deref(),
+        job_description.as_deref(),
+    )
+    .await?;
+
+    let mut settings = match Settings::load(&conf, matches) {
+        Ok(settings) => settings,
+        Err(e) => {
+            let err = ClapError::raw(ErrorKind::InvalidValue, format!("{}\n", e)).with_cmd(&cmd);
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
+        }
+    };
+
+    settings.signature = super::signature(&conf);
+    settings.metadata = Metadata::packet(&name, &position, &company, &resume)
+        .with_title(matches.get_one::<String>("title").map(String::as_str));
+
+    let contact = conf.contact.clone();
+
+    if conf.name.as_ref() != Some(&name) {
+        config::write_settings(&cmd, Some(name.clone()), None)?;
+    }
+
+    super::saved(
+        &cmd,
+        &Format::Pdf.path(output.clone()),
+        crate::builders::packet::build(
+            name,
+            position,
+            company,
+            location,
+            hiring_manager,
+            body,
+            resume,
+            contact,
+            settings,
+            output,
+        ),
+    )
+}
@@ -1,48 +1,29 @@
+use crate::builders::{fit, format::Format, metadata::Metadata, pages, Settings};
 use crate::config;
+use crate::keywords;
 use clap::{
     error::{ContextKind, ContextValue, Error as ClapError, ErrorKind, Result},
     ArgMatches, Command,
 };
+use color_print::cprintln;
 use std::path::PathBuf;
 
-pub fn resume(cmd: Command, matches: &ArgMatches) -> Result<(), ClapError> {
-    let name: String = match matches.get_one::<String>("name") {
-        Some(namearg) => namearg.to_string(),
-        None => match config::load_settings() {
-            Ok(conf) => match conf.name {
-                Some(name) => name,
-                None => {
-                    let mut err = ClapError::new(ErrorKind::MissingRequiredArgument).with_cmd(&cmd);
-                    err.insert(
-                        ContextKind::Usage,
-                        ContextValue::StyledStr(crate::CONFIG_NAME.into()),
-                    );
-                    let e = err.render();
-                    print!("{}", e.ansi());
-                    return Err(err);
-                }
-            },
-            Err(_) => {
-                let mut err = ClapError::new(ErrorKind::MissingRequiredArgument).with_cmd(&cmd);
-                err.insert(ContextKind::InvalidArg, ContextValue::None);
-                err.insert(
-                    ContextKind::SuggestedArg,
-                    ContextValue::String("--name".to_string()),
-                );
-                err.insert(
-                    ContextKind::SuggestedValue,
-                    ContextValue::String("`Johnny Appleseed'".to_string()),
-                );
-                err.insert(
-                    ContextKind::Usage,
-                    ContextValue::StyledStr(crate::CONFIG_NAME.into()),
-                );
-                let e = err.render();
-                print!("{}", e.ansi());
-                return Err(err);
-            }
-        },
+pub async fn resume(cmd: Command, matches: &ArgMatches) -> Result<(), ClapError> {
+    let conf = match config::load_settings() {
+        Ok(conf) => conf,
+        Err(e) => {
+            let err = ClapError::raw(
+                ErrorKind::InvalidValue,
+                format!("Unable to load ~/.cv.config.yaml: {}\n", e),
+            )
+            .with_cmd(&cmd);
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
+        }
     };
+
+    let name = super::resolve_name(&cmd, matches, &conf)?;
     let position: String = match matches.get_one::<String>("position") {
         Some(position) => position.to_owned(),
         None => {
@@ -82,17 +63,113 @@ pub fn resume(cmd: Command, matches: &ArgMatches) -> Result<(), ClapError> {
         }
     };
 
-    match config::load_settings() {
-        Ok(conf) => {
-            if (conf.name.is_some() && conf.name.as_ref().unwrap() != &name)
-                || conf.name.as_ref().is_none()
-            {
-                let _ = config::write_settings(&cmd, Some(conf), Some(name.clone()), None)?;
-            }
+    let mut resume = super::load_resume(&cmd, matches, &conf)?;
+
+    let job_description = super::job_description(&cmd, matches).await?;
+    let max_highlights: Option<usize> = matches.get_one::<usize>("max-highlights").copied();
+
+    // What highlights and skills are ranked against, for tailoring and trimming
+    let query = match &job_description {
+        Some(job) => format!("{}\n{}", position, job),
+        None => position.clone(),
+    };
+    if max_highlights.is_some() || job_description.is_some() {
+        keywords::tailor(&mut resume, &query, max_highlights);
+    }
+
+    let mut settings = match Settings::load(&conf, matches) {
+        Ok(settings) => settings,
+        Err(e) => {
+            let err = ClapError::raw(ErrorKind::InvalidValue, format!("{}\n", e)).with_cmd(&cmd);
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
         }
-        Err(_) => (),
     };
 
-    crate::builders::resume::build(name, position, output);
-    Ok(())
+    settings.metadata = Metadata::resume(
+        &name,
+        &position,
+        matches.get_one::<String>("company").map(String::as_str),
+        &resume,
+    )
+    .with_title(matches.get_one::<String>("title").map(String::as_str));
+
+    let contact = conf.contact.clone();
+
+    if conf.name.as_ref() != Some(&name) {
+        config::write_settings(&cmd, Some(name.clone()), None)?;
+    }
+
+    let fit_pages: Option<u64> = matches.get_one::<u64>("fit-pages").copied();
+    if fit_pages.is_some() && settings.format != Format::Pdf {
+        let err = ClapError::raw(
+            ErrorKind::ArgumentConflict,
+            format!(
+                "--fit-pages only applies to PDFs, not {} files\n",
+                settings.format
+            ),
+        )
+        .with_cmd(&cmd);
+        let e = err.render();
+        print!("{}", e.ansi());
+        return Err(err);
+    }
+    match fit_pages {
+        Some(pages) => {
+            let fitted = match fit::fit(
+                &name,
+                &position,
+                &resume,
+                &contact,
+                &settings,
+                pages as usize,
+                matches.get_flag("trim-highlights"),
+                &query,
+            ) {
+                Ok(fitted) => fitted,
+                Err(e) => {
+                    let err = ClapError::raw(
+                        ErrorKind::InvalidValue,
+                        format!("Unable to render the resume: {}\n", e),
+                    )
+                    .with_cmd(&cmd);
+                    let e = err.render();
+                    print!("{}", e.ansi());
+                    return Err(err);
+                }
+            };
+            let plural = if pages == 1 { "" } else { "s" };
+            if fitted.rendered.pages as u64 <= pages {
+                if fitted.changes.is_empty() {
+                    cprintln!("<green>Fits on {} page{} as is</>", pages, plural);
+                } else {
+                    cprintln!("<bold><green>Fitted to {} page{}:</></>", pages, plural);
+                }
+            } else {
+                cprintln!(
+                    "<bold><yellow>Still {} pages after every adjustment:</></>",
+                    fitted.rendered.pages
+                );
+            }
+            for change in &fitted.changes {
+                println!("  {}", change);
+            }
+            if fitted.rendered.pages as u64 > pages && !matches.get_flag("trim-highlights") {
+                cprintln!(
+                    "<dim>Pass --trim-highlights to let cv drop the least relevant highlights</>"
+                );
+            }
+            super::saved(
+                &cmd,
+                &Format::Pdf.path(output.clone()),
+                pages::save(&fitted.rendered.pdf, output, settings.optimize),
+            )
+        }
+        None => super::saved(
+            &cmd,
+            &settings.format.path(output.clone()),
+            crate::builders::resume::build(name, position, resume, contact, settings, output),
+        ),
+    }
 }
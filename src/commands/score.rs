@@ -0,0 +1,84 @@
+use crate::config;
+use crate::job::JobSource;
+use crate::keywords;
+use clap::{
+    error::{ContextKind, ContextValue, Error as ClapError, ErrorKind, Result},
+    ArgMatches, Command,
+};
+use color_print::cprintln;
+
+pub async fn score(cmd: Command, matches: &ArgMatches) -> Result<(), ClapError> {
+    let conf = match config::load_settings() {
+        Ok(conf) => conf,
+        Err(e) => {
+            let err = ClapError::raw(
+                ErrorKind::InvalidValue,
+                format!("Unable to load ~/.cv.config.yaml: {}\n", e),
+            )
+            .with_cmd(&cmd);
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
+        }
+    };
+
+    let resume = super::load_resume(&cmd, matches, &conf)?;
+
+    let job: String = match JobSource::from_matches(matches) {
+        Some(source) => match source.load().await {
+            Ok(text) => text,
+            Err(e) => {
+                let err = ClapError::raw(
+                    ErrorKind::InvalidValue,
+                    format!("Unable to load the job posting: {}\n", e),
+                )
+                .with_cmd(&cmd);
+                let e = err.render();
+                print!("{}", e.ansi());
+                return Err(err);
+            }
+        },
+        None => {
+            let mut err = ClapError::new(ErrorKind::MissingRequiredArgument).with_cmd(&cmd);
+            err.insert(ContextKind::InvalidArg, ContextValue::None);
+            err.insert(
+                ContextKind::SuggestedArg,
+                ContextValue::String("--job-file".to_string()),
+            );
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
+        }
+    };
+
+    let report = keywords::score(&resume, &job);
+
+    let percentage = report.percentage.round() as u32;
+    match percentage {
+        70.. => cprintln!("<bold>Keyword match:</> <bold><green>{}%</></>", percentage),
+        40..=69 => cprintln!(
+            "<bold>Keyword match:</> <bold><yellow>{}%</></>",
+            percentage
+        ),
+        _ => cprintln!("<bold>Keyword match:</> <bold><red>{}%</></>", percentage),
+    }
+
+    println!();
+    cprintln!("<bold><green>Covered</> ({})</>", report.covered.len());
+    for hit in &report.covered {
+        let sources: Vec<String> = hit.sources.iter().map(|s| s.to_string()).collect();
+        cprintln!(
+            "  <green>✓</> {} <dim>— {}</>",
+            hit.keyword.term,
+            sources.join("; ")
+        );
+    }
+
+    println!();
+    cprintln!("<bold><red>Missing</> ({})</>", report.missing.len());
+    for keyword in &report.missing {
+        cprintln!("  <red>✗</> {}", keyword.term);
+    }
+
+    Ok(())
+}
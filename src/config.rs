@@ -1,4 +1,5 @@
-use crate::builders::utils;
+use crate::builders::{font, pages, paper, resume, theme, utils};
+use crate::generate;
 use clap::{
     error::{Error as ClapError, ErrorKind},
     Command,
@@ -8,32 +9,263 @@ use dotenvy;
 use home::home_dir;
 use serde::{Deserialize, Serialize};
 use serde_yaml;
-use std::{default::Default, fs::OpenOptions, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 #[serde(rename = "CVConfig", rename_all = "snake_case")]
 pub struct ConfigFile {
     pub name: Option<String>,
     pub api_key: Option<String>,
+    /// Which backend generates text: `openai` (the default), `ollama` or `mock`.
+    pub provider: Option<generate::Provider>,
+    /// The model name passed to the provider, e.g. `gpt-4o-mini` or `llama3`.
+    pub model: Option<String>,
+    /// The provider's base URL, when it isn't the provider's default.
+    pub api_base: Option<String>,
     pub resume: Option<Resume>,
+    pub cover_letter: Option<CoverLetter>,
+    /// A built-in theme name, a path to a theme file, or the theme itself.
+    pub theme: Option<theme::ThemeSetting>,
+    /// The bundled weights or a directory of TTF files to set documents in.
+    pub font: Option<font::FontSetting>,
+    /// The default page size. Picked from the locale when unset.
+    pub paper: Option<paper::Paper>,
+    /// The default resume layout: `classic`, `sidebar` or `compact`.
+    pub layout: Option<resume::ResumeLayout>,
+    /// Headers and page numbers for the resume and cover letter.
+    pub pages: Option<pages::Pages>,
+    /// Shrink the PDFs with Ghostscript as if `--optimize` were always passed.
+    pub optimize: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contact: Vec<utils::ContactItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct CoverLetter {
+    /// The template used for the letter body when `--template` isn't given.
+    pub template: Option<PathBuf>,
+    /// A PNG or JPEG of your signature, drawn above your typed name.
+    pub signature: Option<PathBuf>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Resume {
-    skills: Vec<utils::Skill>,
-    employment: Vec<utils::WorkExperience>,
-    education: Vec<utils::Degree>,
-    projects: Vec<utils::Project>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub skills: Vec<utils::Skill>,
+    #[serde(default)]
+    pub employment: Vec<utils::WorkExperience>,
+    #[serde(default)]
+    pub education: Vec<utils::Degree>,
+    #[serde(default)]
+    pub projects: Vec<utils::Project>,
+    /// Named versions of the resume, selected with `cv resume --variant`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variants: BTreeMap<String, Variant>,
 }
 
-impl Default for ConfigFile {
-    fn default() -> Self {
-        Self {
-            name: None,
-            api_key: None,
-            resume: None,
+/// The differences between a named resume variant and the base resume.
+/// Anything left unset is inherited from the base.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Variant {
+    pub summary: Option<String>,
+    /// Replace the base lists entirely.
+    pub skills: Option<Vec<utils::Skill>>,
+    pub employment: Option<Vec<utils::WorkExperience>>,
+    pub education: Option<Vec<utils::Degree>>,
+    pub projects: Option<Vec<utils::Project>>,
+    /// Keep only these skill sections, jobs (by company) and projects (by
+    /// title or nickname), in the order given.
+    pub only_skills: Option<Vec<String>>,
+    pub only_employment: Option<Vec<String>>,
+    pub only_projects: Option<Vec<String>>,
+    /// Replacement highlights for individual jobs, keyed by company.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub highlights: BTreeMap<String, Vec<String>>,
+}
+
+/// Picks the entries named in `names` out of `items`, in the order of `names`.
+/// Names are matched case-insensitively and ignore a trailing `:`.
+fn select<T: Clone>(
+    items: &[T],
+    names: &[String],
+    what: &str,
+    keys: impl Fn(&T) -> Vec<String>,
+) -> Result<Vec<T>, String> {
+    let normalize = |s: &str| s.trim().trim_end_matches(':').trim().to_lowercase();
+    names
+        .iter()
+        .map(|name| {
+            items
+                .iter()
+                .find(|item| keys(item).iter().any(|k| normalize(k) == normalize(name)))
+                .cloned()
+                .ok_or_else(|| format!("the resume has no {} named `{}`", what, name))
+        })
+        .collect()
+}
+
+impl Resume {
+    /// Builds the named variant on top of this resume.
+    pub fn variant(&self, name: &str) -> Result<Resume, String> {
+        let variant = self
+            .variants
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+            .ok_or_else(|| {
+                if self.variants.is_empty() {
+                    format!(
+                        "there's no variant named `{}`; no variants are defined",
+                        name
+                    )
+                } else {
+                    format!(
+                        "there's no variant named `{}` (expected one of: {})",
+                        name,
+                        self.variants
+                            .keys()
+                            .cloned()
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    )
+                }
+            })?;
+
+        let mut resume = Resume {
+            summary: variant.summary.clone().or_else(|| self.summary.clone()),
+            skills: variant
+                .skills
+                .clone()
+                .unwrap_or_else(|| self.skills.clone()),
+            employment: variant
+                .employment
+                .clone()
+                .unwrap_or_else(|| self.employment.clone()),
+            education: variant
+                .education
+                .clone()
+                .unwrap_or_else(|| self.education.clone()),
+            projects: variant
+                .projects
+                .clone()
+                .unwrap_or_else(|| self.projects.clone()),
+            variants: BTreeMap::new(),
+        };
+
+        if let Some(names) = &variant.only_skills {
+            resume.skills = select(&resume.skills, names, "skill section", |s| {
+                vec![s.section.clone()]
+            })?;
         }
+        if let Some(names) = &variant.only_employment {
+            resume.employment = select(&resume.employment, names, "job at a company", |j| {
+                vec![j.company.clone()]
+            })?;
+        }
+        if let Some(names) = &variant.only_projects {
+            resume.projects = select(&resume.projects, names, "project", |p| {
+                let mut keys = vec![p.title.clone()];
+                keys.extend(p.nickname.clone());
+                keys
+            })?;
+        }
+        for (company, highlights) in &variant.highlights {
+            match resume
+                .employment
+                .iter_mut()
+                .find(|j| j.company.eq_ignore_ascii_case(company))
+            {
+                Some(job) => job.highlights = highlights.clone(),
+                None => {
+                    return Err(format!(
+                        "variant `{}` has highlights for `{}`, which isn't one of its jobs",
+                        name, company
+                    ))
+                }
+            }
+        }
+
+        Ok(resume)
+    }
+
+    /// Checks that the resume has something to render and that none of the
+    /// entries have blank required fields. The error lists every problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut problems: Vec<String> = Vec::new();
+
+        if self.skills.is_empty()
+            && self.employment.is_empty()
+            && self.education.is_empty()
+            && self.projects.is_empty()
+        {
+            problems.push(
+                "the resume block has no skills, employment, education or projects".to_string(),
+            );
+        }
+
+        for (i, skill) in self.skills.iter().enumerate() {
+            if skill.section.trim().is_empty() {
+                problems.push(format!("resume.skills[{}].section is empty", i));
+            }
+            if skill.skills.is_empty() {
+                problems.push(format!("resume.skills[{}].skills is empty", i));
+            }
+        }
+
+        for (i, job) in self.employment.iter().enumerate() {
+            for (field, value) in [
+                ("position", &job.position),
+                ("company", &job.company),
+                ("start_date", &job.start_date),
+                ("end_date", &job.end_date),
+            ] {
+                if value.trim().is_empty() {
+                    problems.push(format!("resume.employment[{}].{} is empty", i, field));
+                }
+            }
+        }
+
+        for (i, deg) in self.education.iter().enumerate() {
+            for (field, value) in [
+                ("university", &deg.university),
+                ("degree", &deg.degree),
+                ("year", &deg.year),
+            ] {
+                if value.trim().is_empty() {
+                    problems.push(format!("resume.education[{}].{} is empty", i, field));
+                }
+            }
+        }
+
+        for (i, proj) in self.projects.iter().enumerate() {
+            if proj.title.trim().is_empty() {
+                problems.push(format!("resume.projects[{}].title is empty", i));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("\n"))
+        }
+    }
+}
+
+/// Expands a leading `~/` in a configured path to the home directory.
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
     }
 }
 
@@ -45,24 +277,21 @@ pub fn load_settings() -> Result<ConfigFile, ConfigError> {
     envfile.push(".cvrc");
     dotenvy::from_path(envfile.clone()).ok();
 
-    let mut mainfile: PathBuf = match home_dir() {
-        Some(path) => path,
-        None => panic!("Impossible to get your home dir!"),
-    };
-    mainfile.push(".cv.config.yaml");
+    let mainfile = settings_path();
 
     let mut builder = Config::builder();
     builder = builder.add_source(File::from(envfile).format(FileFormat::Ini).required(false));
-    builder = builder.add_source(
-        Environment::with_prefix("CV_")
-            .ignore_empty(true)
-            .keep_prefix(false),
-    );
     builder = builder.add_source(
         File::from(mainfile)
             .format(FileFormat::Yaml)
             .required(false),
     );
+    // `CV_*` variables (e.g. `CV_API_BASE`) override both files
+    builder = builder.add_source(
+        Environment::with_prefix("CV")
+            .ignore_empty(true)
+            .keep_prefix(false),
+    );
 
     let config = builder.build()?;
     let configfile = config.try_deserialize::<ConfigFile>()?;
@@ -70,15 +299,46 @@ pub fn load_settings() -> Result<ConfigFile, ConfigError> {
     Ok(configfile)
 }
 
+/// `~/.cv.config.yaml`.
+fn settings_path() -> PathBuf {
+    let mut mainfile: PathBuf = match home_dir() {
+        Some(path) => path,
+        None => panic!("Impossible to get your home dir!"),
+    };
+    mainfile.push(".cv.config.yaml");
+    mainfile
+}
+
+/// The config file alone, without `~/.cvrc` or `CV_*` variables, so that
+/// what's written back never picks up values meant for one run.
+pub fn file_settings() -> Result<ConfigFile, String> {
+    match fs::read_to_string(settings_path()) {
+        Ok(text) if text.trim().is_empty() => Ok(ConfigFile::default()),
+        Ok(text) => serde_yaml::from_str(&text).map_err(|e| e.to_string()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(ConfigFile::default()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Sets the name and API key in the config file, leaving the rest as it is
+/// on disk.
 pub fn write_settings(
     cmd: &Command,
-    existing: Option<ConfigFile>,
     name: Option<String>,
     api_key: Option<String>,
 ) -> Result<(), ClapError> {
-    let mut configfile = match existing {
-        Some(existing) => existing,
-        None => ConfigFile::default(),
+    let mut configfile = match file_settings() {
+        Ok(configfile) => configfile,
+        Err(e) => {
+            let err = ClapError::raw(
+                ErrorKind::Io,
+                format!("Unable to read ~/.cv.config.yaml: {}\n", e),
+            )
+            .with_cmd(cmd);
+            let e = err.render();
+            print!("{}", e.ansi());
+            return Err(err);
+        }
     };
     if name.is_some() {
         configfile.name = name;
@@ -86,18 +346,16 @@ pub fn write_settings(
     if api_key.is_some() {
         configfile.api_key = api_key;
     }
+    save_settings(cmd, &configfile)
+}
 
-    let mut mainfile: PathBuf = match home_dir() {
-        Some(path) => path,
-        None => panic!("Impossible to get your home dir!"),
-    };
-    mainfile.push(".cv.config.yaml");
-
+/// Replaces the config file with `configfile`.
+pub fn save_settings(cmd: &Command, configfile: &ConfigFile) -> Result<(), ClapError> {
     let writer = match OpenOptions::new()
         .write(true)
         .truncate(true)
         .create(true)
-        .open(mainfile)
+        .open(settings_path())
     {
         Ok(file) => file,
         Err(_) => {
@@ -108,7 +366,7 @@ pub fn write_settings(
         }
     };
 
-    match serde_yaml::to_writer(writer, &configfile) {
+    match serde_yaml::to_writer(writer, configfile) {
         Ok(_) => Ok(()),
         Err(_) => {
             let err = ClapError::new(ErrorKind::Io).with_cmd(cmd);
@@ -118,3 +376,128 @@ pub fn write_settings(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resume() -> Resume {
+        serde_yaml::from_str(
+            r#"
+summary: Generalist engineer.
+skills:
+  - { section: "Languages: ", skills: [Rust, TypeScript] }
+  - { section: "Cloud: ", skills: [AWS] }
+employment:
+  - { position: Engineer, company: Acme, location: Remote, start_date: "2021", end_date: Present, highlights: [Built the API] }
+  - { position: Developer, company: Initech, location: Austin, start_date: "2018", end_date: "2021", highlights: [Built the UI] }
+projects:
+  - { nickname: cv, title: Resume builder, organization: Personal, year: "2023" }
+  - { title: Dotfiles, organization: Personal, year: "2020" }
+variants:
+  backend:
+    summary: Backend engineer.
+    only_skills: ["languages"]
+    only_employment: [Initech, acme]
+    only_projects: [CV]
+    highlights:
+      Initech: [Wrote the billing service]
+  frontend:
+    only_employment: [Initech]
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn variant_overrides_and_filters_the_base() {
+        let backend = resume().variant("Backend").unwrap();
+        assert_eq!(backend.summary.as_deref(), Some("Backend engineer."));
+        assert_eq!(backend.skills.len(), 1);
+        assert_eq!(backend.skills[0].skills, ["Rust", "TypeScript"]);
+        let companies: Vec<&str> = backend
+            .employment
+            .iter()
+            .map(|j| j.company.as_str())
+            .collect();
+        assert_eq!(companies, ["Initech", "Acme"]);
+        assert_eq!(
+            backend.employment[0].highlights,
+            ["Wrote the billing service"]
+        );
+        assert_eq!(backend.employment[1].highlights, ["Built the API"]);
+        assert_eq!(backend.projects.len(), 1);
+        assert_eq!(backend.projects[0].title, "Resume builder");
+        assert!(backend.variants.is_empty());
+
+        // Anything the variant leaves out comes from the base
+        let frontend = resume().variant("frontend").unwrap();
+        assert_eq!(frontend.summary.as_deref(), Some("Generalist engineer."));
+        assert_eq!(frontend.skills, resume().skills);
+        assert_eq!(frontend.projects, resume().projects);
+    }
+
+    #[test]
+    fn unknown_variants_list_the_ones_there_are() {
+        assert_eq!(
+            resume().variant("mobile").unwrap_err(),
+            "there's no variant named `mobile` (expected one of: backend, frontend)"
+        );
+        let base = Resume {
+            variants: BTreeMap::new(),
+            ..resume()
+        };
+        assert_eq!(
+            base.variant("mobile").unwrap_err(),
+            "there's no variant named `mobile`; no variants are defined"
+        );
+    }
+
+    #[test]
+    fn variants_must_name_entries_that_exist() {
+        let mut base = resume();
+        let backend = base.variants.get_mut("backend").unwrap();
+        backend
+            .highlights
+            .insert("Globex".to_string(), vec!["Sold things".to_string()]);
+        assert_eq!(
+            base.variant("backend").unwrap_err(),
+            "variant `backend` has highlights for `Globex`, which isn't one of its jobs"
+        );
+
+        // A job the variant filtered out can't take highlights either
+        let mut base = resume();
+        let frontend = base.variants.get_mut("frontend").unwrap();
+        frontend
+            .highlights
+            .insert("Acme".to_string(), vec!["Sold things".to_string()]);
+        assert!(base.variant("frontend").is_err());
+
+        let mut base = resume();
+        base.variants.get_mut("frontend").unwrap().only_projects = Some(vec!["Blog".to_string()]);
+        assert_eq!(
+            base.variant("frontend").unwrap_err(),
+            "the resume has no project named `Blog`"
+        );
+    }
+
+    #[test]
+    fn validate_lists_every_problem() {
+        assert_eq!(resume().validate(), Ok(()));
+        assert_eq!(
+            Resume::default().validate().unwrap_err(),
+            "the resume block has no skills, employment, education or projects"
+        );
+
+        let mut broken = resume();
+        broken.skills[1].skills.clear();
+        broken.employment[0].company = " ".to_string();
+        broken.projects[1].title = String::new();
+        assert_eq!(
+            broken.validate().unwrap_err(),
+            "resume.skills[1].skills is empty\n\
+             resume.employment[0].company is empty\n\
+             resume.projects[1].title is empty"
+        );
+    }
+}
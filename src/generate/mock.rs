@@ -0,0 +1,18 @@
+use crate::generate::{Generation, TextGenerator};
+
+/// An offline generator that never touches the network. The reply is built
+/// only from the prompt, so the same input always gives the same output.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Mock;
+
+impl TextGenerator for Mock {
+    fn generate<'a>(&'a self, _system: &'a str, prompt: &'a str) -> Generation<'a> {
+        let first = prompt.lines().next().unwrap_or_default().trim();
+        let words = prompt.split_whitespace().count();
+        let reply = format!(
+            "This is a mock reply to: {}\n\nThe prompt had {} words.",
+            first, words
+        );
+        Box::pin(async move { Ok(reply) })
+    }
+}
@@ -0,0 +1,187 @@
+pub mod mock;
+pub mod ollama;
+pub mod openai;
+
+use crate::builders::template::paragraphs;
+use crate::config::{ConfigFile, Resume};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write as _, future::Future, pin::Pin};
+
+/// The pending reply from a [`TextGenerator`].
+pub type Generation<'a> = Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>>;
+
+/// Anything that can turn a system message and a prompt into text.
+pub trait TextGenerator {
+    fn generate<'a>(&'a self, system: &'a str, prompt: &'a str) -> Generation<'a>;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Provider {
+    #[default]
+    Openai,
+    Ollama,
+    Mock,
+}
+
+/// Builds the generator selected by `provider`, `model` and `api_base` in the
+/// config.
+pub fn from_config(conf: &ConfigFile) -> Result<Box<dyn TextGenerator + Send + Sync>, String> {
+    match conf.provider.unwrap_or_default() {
+        Provider::Openai => Ok(Box::new(openai::OpenAi {
+            api_base: conf
+                .api_base
+                .clone()
+                .unwrap_or_else(|| openai::DEFAULT_API_BASE.to_string()),
+            api_key: conf
+                .api_key
+                .clone()
+                .ok_or_else(|| "no API key has been set".to_string())?,
+            model: conf
+                .model
+                .clone()
+                .unwrap_or_else(|| openai::DEFAULT_MODEL.to_string()),
+        })),
+        Provider::Ollama => Ok(Box::new(ollama::Ollama {
+            api_base: conf
+                .api_base
+                .clone()
+                .unwrap_or_else(|| ollama::DEFAULT_API_BASE.to_string()),
+            model: conf
+                .model
+                .clone()
+                .unwrap_or_else(|| ollama::DEFAULT_MODEL.to_string()),
+        })),
+        Provider::Mock => Ok(Box::new(mock::Mock)),
+    }
+}
+
+static COVER_LETTER_SYSTEM: &str = "You write concise, specific cover letters. \
+Reply with only the body paragraphs of the letter, separated by blank lines. \
+Do not include a date, address, greeting, sign-off or signature. \
+Only mention experience that appears in the candidate's resume.";
+
+/// Everything the model is told about a cover letter it should write.
+#[derive(Debug, Clone)]
+pub struct CoverLetterRequest<'a> {
+    pub name: &'a str,
+    pub company: &'a str,
+    pub position: Option<&'a str>,
+    pub location: Option<&'a str>,
+    pub hiring_manager: Option<&'a str>,
+    pub job_description: Option<&'a str>,
+    pub resume: &'a Resume,
+}
+
+/// Writes the resume out as plain text for use in a prompt.
+pub fn resume_prompt(resume: &Resume) -> String {
+    let mut out = String::new();
+    if let Some(summary) = &resume.summary {
+        let _ = writeln!(out, "Summary: {}\n", summary.replace("**", ""));
+    }
+    if !resume.skills.is_empty() {
+        let _ = writeln!(out, "Skills:");
+        for skill in &resume.skills {
+            let section = skill.section.trim().trim_end_matches(':');
+            let _ = writeln!(out, "- {}: {}", section, skill.skills.join(", "));
+        }
+        out.push('\n');
+    }
+    if !resume.employment.is_empty() {
+        let _ = writeln!(out, "Experience:");
+        for job in &resume.employment {
+            let _ = writeln!(
+                out,
+                "- {}, {} ({}), {} - {}",
+                job.position, job.company, job.location, job.start_date, job.end_date
+            );
+            for highlight in &job.highlights {
+                let _ = writeln!(out, "  * {}", highlight);
+            }
+        }
+        out.push('\n');
+    }
+    if !resume.education.is_empty() {
+        let _ = writeln!(out, "Education:");
+        for deg in &resume.education {
+            let _ = writeln!(
+                out,
+                "- {}, {} ({}): {}",
+                deg.degree, deg.university, deg.year, deg.description
+            );
+        }
+        out.push('\n');
+    }
+    if !resume.projects.is_empty() {
+        let _ = writeln!(out, "Projects:");
+        for proj in &resume.projects {
+            match &proj.nickname {
+                Some(nickname) => {
+                    let _ = writeln!(
+                        out,
+                        "- {} ({}), {}, {}",
+                        nickname, proj.title, proj.organization, proj.year
+                    );
+                }
+                None => {
+                    let _ = writeln!(
+                        out,
+                        "- {}, {}, {}",
+                        proj.title, proj.organization, proj.year
+                    );
+                }
+            }
+            if let Some(description) = &proj.description {
+                let _ = writeln!(out, "  {}", description);
+            }
+        }
+    }
+    out
+}
+
+pub fn cover_letter_prompt(req: &CoverLetterRequest) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "Write the body of a cover letter from {} to {}.",
+        req.name, req.company
+    );
+    match req.position {
+        Some(position) => {
+            let _ = writeln!(out, "The position is: {}.", position);
+        }
+        None => {
+            let _ = writeln!(
+                out,
+                "No specific position was given; write a general letter."
+            );
+        }
+    }
+    if let Some(location) = req.location {
+        let _ = writeln!(out, "The office is in {}.", location);
+    }
+    if let Some(hiring_manager) = req.hiring_manager {
+        let _ = writeln!(out, "The letter is addressed to {}.", hiring_manager);
+    }
+    if let Some(job) = req.job_description {
+        let _ = writeln!(out, "\nJob description:\n{}", job.trim());
+    }
+    let _ = writeln!(out, "\nCandidate resume:\n{}", resume_prompt(req.resume));
+    out
+}
+
+/// Asks the generator for the body paragraphs of a cover letter.
+pub async fn cover_letter_body(
+    generator: &(dyn TextGenerator + Send + Sync),
+    req: &CoverLetterRequest<'_>,
+) -> Result<Vec<String>, String> {
+    let text = generator
+        .generate(COVER_LETTER_SYSTEM, &cover_letter_prompt(req))
+        .await?;
+    let body = paragraphs(&text);
+    if body.is_empty() {
+        Err("the model didn't return any paragraphs".to_string())
+    } else {
+        Ok(body)
+    }
+}
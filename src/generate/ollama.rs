@@ -0,0 +1,25 @@
+use crate::generate::{openai, Generation, TextGenerator};
+
+pub static DEFAULT_API_BASE: &str = "http://localhost:11434/v1";
+pub static DEFAULT_MODEL: &str = "llama3";
+
+/// A model served locally by Ollama or llama.cpp's `llama-server`. Both expose
+/// an OpenAI-compatible `/v1/chat/completions` route that needs no API key,
+/// so nothing leaves the machine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ollama {
+    pub api_base: String,
+    pub model: String,
+}
+
+impl TextGenerator for Ollama {
+    fn generate<'a>(&'a self, system: &'a str, prompt: &'a str) -> Generation<'a> {
+        Box::pin(openai::chat(
+            &self.api_base,
+            None,
+            &self.model,
+            system,
+            prompt,
+        ))
+    }
+}
@@ -0,0 +1,108 @@
+use crate::generate::{Generation, TextGenerator};
+use serde::{Deserialize, Serialize};
+
+pub static DEFAULT_API_BASE: &str = "https://api.openai.com/v1";
+pub static DEFAULT_MODEL: &str = "gpt-4o-mini";
+
+#[derive(Debug, Clone, Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+    temperature: f32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ChatChoice {
+    message: ChatReply,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ChatReply {
+    content: Option<String>,
+}
+
+/// The OpenAI chat-completions API, or anything that speaks it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenAi {
+    pub api_base: String,
+    pub api_key: String,
+    pub model: String,
+}
+
+impl TextGenerator for OpenAi {
+    fn generate<'a>(&'a self, system: &'a str, prompt: &'a str) -> Generation<'a> {
+        Box::pin(chat(
+            &self.api_base,
+            Some(&self.api_key),
+            &self.model,
+            system,
+            prompt,
+        ))
+    }
+}
+
+/// Sends a single system + user exchange to an OpenAI-compatible
+/// `/chat/completions` endpoint and returns the reply text. Local servers
+/// that don't check credentials can be called without an `api_key`.
+pub async fn chat(
+    api_base: &str,
+    api_key: Option<&str>,
+    model: &str,
+    system: &str,
+    prompt: &str,
+) -> Result<String, String> {
+    let url = format!("{}/chat/completions", api_base.trim_end_matches('/'));
+    let body = ChatRequest {
+        model,
+        messages: vec![
+            ChatMessage {
+                role: "system",
+                content: system,
+            },
+            ChatMessage {
+                role: "user",
+                content: prompt,
+            },
+        ],
+        temperature: 0.7,
+    };
+
+    let mut request = reqwest::Client::new().post(&url).json(&body);
+    if let Some(api_key) = api_key {
+        request = request.bearer_auth(api_key);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("request to {} failed: {}", url, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("{} returned {}: {}", url, status, text.trim()));
+    }
+
+    let reply: ChatResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("unexpected response from {}: {}", url, e))?;
+
+    reply
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|choice| choice.message.content)
+        .filter(|content| !content.trim().is_empty())
+        .ok_or_else(|| format!("{} returned an empty completion", url))
+}
@@ -0,0 +1,244 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::Path,
+};
+
+/// Where a job posting comes from, as given on the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobSource {
+    Text(String),
+    /// A file path, or `-` for stdin.
+    File(String),
+    Url(String),
+}
+
+impl JobSource {
+    /// Reads the `job-description`, `job-file` and `job-url` arguments, if the
+    /// command has them.
+    pub fn from_matches(matches: &clap::ArgMatches) -> Option<Self> {
+        let get = |id: &str| match matches.try_get_one::<String>(id) {
+            Ok(value) => value.cloned(),
+            Err(_) => None,
+        };
+        get("job-description")
+            .map(JobSource::Text)
+            .or_else(|| get("job-file").map(JobSource::File))
+            .or_else(|| get("job-url").map(JobSource::Url))
+    }
+
+    /// Loads the posting as plain text.
+    pub async fn load(&self) -> Result<String, String> {
+        let text = match self {
+            JobSource::Text(text) => text.clone(),
+            JobSource::File(path) => {
+                let text = if path == "-" {
+                    let mut text = String::new();
+                    io::stdin()
+                        .read_to_string(&mut text)
+                        .map_err(|e| format!("unable to read the job posting from stdin: {}", e))?;
+                    text
+                } else {
+                    fs::read_to_string(Path::new(path))
+                        .map_err(|e| format!("unable to read {}: {}", path, e))?
+                };
+                if looks_like_html(&text) {
+                    html_to_text(&text)
+                } else {
+                    text
+                }
+            }
+            JobSource::Url(url) => fetch(url).await?,
+        };
+        if text.trim().is_empty() {
+            Err("the job posting is empty".to_string())
+        } else {
+            Ok(text.trim().to_string())
+        }
+    }
+}
+
+/// Downloads a job posting, converting HTML pages to text.
+pub async fn fetch(url: &str) -> Result<String, String> {
+    let response = reqwest::get(url)
+        .await
+        .map_err(|e| format!("unable to fetch {}: {}", url, e))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("{} returned {}", url, status));
+    }
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.contains("html"))
+        .unwrap_or(false);
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("unable to read the response from {}: {}", url, e))?;
+    if is_html || looks_like_html(&body) {
+        Ok(html_to_text(&body))
+    } else {
+        Ok(body)
+    }
+}
+
+fn looks_like_html(text: &str) -> bool {
+    let start = text.trim_start().to_ascii_lowercase();
+    start.starts_with("<!doctype html") || start.starts_with("<html")
+}
+
+static BLOCK_TAGS: [&str; 19] = [
+    "p", "div", "br", "li", "ul", "ol", "h1", "h2", "h3", "h4", "h5", "h6", "tr", "table",
+    "section", "article", "header", "footer", "title",
+];
+
+/// Strips the markup from an HTML page, keeping paragraphs and list items on
+/// their own lines. Scripts, styles and comments are dropped.
+pub fn html_to_text(html: &str) -> String {
+    let mut out = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        // Line breaks in the source are only spaces; the tags decide the lines
+        out.push_str(&decode_entities(&rest[..start]).replace(['\n', '\r'], " "));
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = match comment.find("-->") {
+                Some(end) => &comment[end + 3..],
+                None => "",
+            };
+            continue;
+        }
+
+        let end = match rest.find('>') {
+            Some(end) => end,
+            None => break,
+        };
+        let tag = rest[1..end].trim();
+        rest = &rest[end + 1..];
+
+        let closing = tag.starts_with('/');
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        if !closing && (name == "script" || name == "style") {
+            let close = format!("</{}", name);
+            rest = match rest.to_ascii_lowercase().find(&close) {
+                Some(i) => match rest[i..].find('>') {
+                    Some(j) => &rest[i + j + 1..],
+                    None => "",
+                },
+                None => "",
+            };
+            continue;
+        }
+
+        if BLOCK_TAGS.contains(&name.as_str()) {
+            out.push('\n');
+            if name == "li" && !closing {
+                out.push_str("- ");
+            }
+        }
+    }
+    out.push_str(&decode_entities(rest).replace(['\n', '\r'], " "));
+
+    let mut lines: Vec<String> = Vec::new();
+    for line in out.lines() {
+        let line = line.split_whitespace().collect::<Vec<&str>>().join(" ");
+        if !line.is_empty() && line != "-" {
+            lines.push(line);
+        }
+    }
+    lines.join("\n")
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest[1..].find(';').filter(|&end| end <= 8).and_then(|end| {
+            let entity = &rest[1..end + 1];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => match entity.strip_prefix('#') {
+                    Some(hex) if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16)
+                        .ok()
+                        .and_then(char::from_u32),
+                    Some(dec) => dec.parse::<u32>().ok().and_then(char::from_u32),
+                    None => None,
+                },
+            };
+            c.map(|c| (c, end + 2))
+        });
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_scripts_styles_and_comments() {
+        let html = "<html><head><title>Job</title><style>p { color: red }</style>\
+            <script>document.write(\"<p>hidden</p>\")</script></head>\
+            <body><!-- tracking --><p>Hello</p><SCRIPT type=\"x\">1 < 2</SCRIPT></body></html>";
+        assert_eq!(html_to_text(html), "Job\nHello");
+    }
+
+    #[test]
+    fn decodes_entities() {
+        let html =
+            "<p>R&amp;D &lt;team&gt; &quot;fast&quot; caf&#233; &#x2014; A&nbsp;B &bogus; AT&T</p>";
+        assert_eq!(
+            html_to_text(html),
+            "R&D <team> \"fast\" caf\u{e9} \u{2014} A B &bogus; AT&T"
+        );
+    }
+
+    #[test]
+    fn breaks_lines_at_list_items_and_brs() {
+        let html = "<ul><li>Rust</li><li>Go</li></ul>Line one<br>Line two<br/>Line three";
+        assert_eq!(
+            html_to_text(html),
+            "- Rust\n- Go\nLine one\nLine two\nLine three"
+        );
+    }
+
+    #[test]
+    fn collapses_whitespace() {
+        let html = "<p>  lots   of\n\t space  </p>\n\n<div>   </div><li> </li><p>next</p>";
+        assert_eq!(html_to_text(html), "lots of space\nnext");
+    }
+
+    #[test]
+    fn recognises_html_pages() {
+        assert!(looks_like_html("  <!DOCTYPE html><html></html>"));
+        assert!(looks_like_html("<HTML lang=\"en\">"));
+        assert!(!looks_like_html("We need <b>Rust</b>"));
+    }
+}
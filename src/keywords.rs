@@ -0,0 +1,640 @@
+use crate::config::Resume;
+use std::{collections::BTreeMap, fmt};
+
+/// Words that carry no signal in a job posting.
+#[rustfmt::skip]
+static STOPWORDS: &[&str] = &[
+    "a", "about", "above", "across", "after", "all", "also", "an", "and", "any", "are", "as",
+    "at", "be", "been", "being", "both", "but", "by", "can", "could", "do", "does", "each",
+    "etc", "every", "for", "from", "had", "has", "have", "having", "he", "her", "here", "his",
+    "how", "i", "if", "in", "into", "is", "it", "its", "just", "like", "may", "me", "more",
+    "most", "must", "my", "no", "not", "of", "on", "one", "only", "or", "other", "our", "ours",
+    "out", "over", "own", "per", "plus", "same", "she", "should", "so", "some", "such", "than",
+    "that", "the", "their", "them", "then", "there", "these", "they", "this", "those",
+    "through", "to", "too", "under", "up", "us", "very", "was", "we", "were", "what", "when",
+    "where", "which", "while", "who", "whom", "why", "will", "with", "within", "without",
+    "would", "you", "your", "yours",
+    // Boilerplate that shows up in nearly every posting
+    "ability", "able", "applicant", "applicants", "apply", "benefit", "benefits", "build",
+    "building", "candidate", "candidates", "company", "environment", "equal", "experience",
+    "experienced", "help", "ideal", "include", "includes", "including", "job", "join",
+    "looking", "new", "opportunity", "position", "preferred", "qualification", "qualifications",
+    "related", "required", "requirement", "requirements", "responsibilities", "responsibility",
+    "role", "salary", "seeking", "senior", "skill", "skills", "strong", "team", "teams",
+    "using", "well", "work", "working", "year", "years",
+    // Verbs and filler from the way postings are written
+    "day", "e.g", "ensure", "excellent", "fast-paced", "get", "great", "hands", "i.e", "know",
+    "make", "need", "nice", "passionate", "self-starter", "use", "want", "world-class",
+];
+
+/// Whether a (stemmed) token is a stopword. Hyphenated words made only of
+/// stopwords, like `across-team`, are stopwords too.
+pub fn is_stopword(token: &str) -> bool {
+    let listed =
+        |t: &str| STOPWORDS.contains(&t) || STOPWORDS.contains(&format!("{}s", t).as_str());
+    listed(token)
+        || (token.contains('-') && token.split('-').all(|part| part.is_empty() || listed(part)))
+}
+
+/// Reduces plural forms so `pipelines` matches `pipeline`.
+fn stem(token: &str) -> String {
+    if token.len() > 3
+        && token.ends_with('s')
+        && !token.ends_with("ss")
+        && token.chars().all(|c| c.is_ascii_alphabetic())
+    {
+        token[..token.len() - 1].to_string()
+    } else {
+        token.to_string()
+    }
+}
+
+/// Splits text into lowercase words. Characters that are part of technology
+/// names (`c++`, `c#`, `node.js`, `ci/cd`) are kept inside words, and words
+/// without any letters (`5+`, `2020`) are dropped.
+pub fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || "+#./-".contains(c)))
+        .map(|t| t.trim_matches(|c: char| ".,/-".contains(c)))
+        .filter(|t| t.chars().any(|c| c.is_alphabetic()))
+        .map(|t| t.to_string())
+        .collect()
+}
+
+/// Splits text into lowercase, stemmed tokens for matching.
+pub fn tokenize(text: &str) -> Vec<String> {
+    words(text).iter().map(|w| stem(w)).collect()
+}
+
+/// A term from the job posting and how much it matters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keyword {
+    /// The term as it first appeared in the posting, e.g. `machine learning`.
+    pub term: String,
+    /// The stemmed tokens of the term, e.g. `["machine", "learning"]`.
+    pub tokens: Vec<String>,
+    pub weight: f32,
+}
+
+/// The resume's skills alongside their token sequences.
+fn skill_phrases(resume: &Resume) -> Vec<(String, Vec<String>)> {
+    resume
+        .skills
+        .iter()
+        .flat_map(|s| s.skills.iter())
+        .map(|s| (s.clone(), tokenize(s)))
+        .filter(|(_, t)| !t.is_empty())
+        .collect()
+}
+
+fn count_phrase(tokens: &[String], phrase: &[String]) -> usize {
+    if phrase.is_empty() || phrase.len() > tokens.len() {
+        return 0;
+    }
+    tokens
+        .windows(phrase.len())
+        .filter(|w| *w == phrase)
+        .count()
+}
+
+/// Pulls the `limit` most important terms out of a job posting. Single words
+/// and two-word phrases are weighted by how often they appear; phrases and
+/// anything that's also one of the resume's skills count for more.
+pub fn extract(job: &str, resume: &Resume, limit: usize) -> Vec<Keyword> {
+    let surface = words(job);
+    let tokens: Vec<String> = surface.iter().map(|w| stem(w)).collect();
+    let mut weights: BTreeMap<Vec<String>, f32> = BTreeMap::new();
+    let mut terms: BTreeMap<Vec<String>, String> = BTreeMap::new();
+    for n in 1..=2 {
+        for (i, gram) in tokens.windows(n).enumerate() {
+            terms
+                .entry(gram.to_vec())
+                .or_insert_with(|| surface[i..i + n].join(" "));
+        }
+    }
+
+    for token in tokens.iter().filter(|t| !is_stopword(t) && t.len() > 1) {
+        *weights.entry(vec![token.clone()]).or_default() += 1.0;
+    }
+    for pair in tokens.windows(2) {
+        if pair.iter().all(|t| !is_stopword(t) && t.len() > 1) {
+            *weights.entry(pair.to_vec()).or_default() += 1.5;
+        }
+    }
+    // A two-word phrase seen once is usually just two adjacent words
+    weights.retain(|k, w| k.len() == 1 || *w > 1.5);
+
+    for (skill, phrase) in skill_phrases(resume) {
+        let count = count_phrase(&tokens, &phrase);
+        if count > 0 && phrase.iter().any(|t| !is_stopword(t)) {
+            terms.entry(phrase.clone()).or_insert(skill);
+            *weights.entry(phrase).or_default() += 2.0 * count as f32;
+        }
+    }
+
+    let mut keywords: Vec<Keyword> = weights
+        .into_iter()
+        .map(|(tokens, weight)| Keyword {
+            term: terms
+                .get(&tokens)
+                .cloned()
+                .unwrap_or_else(|| tokens.join(" ")),
+            tokens,
+            weight,
+        })
+        .collect();
+    keywords.sort_by(|a, b| {
+        b.weight
+            .partial_cmp(&a.weight)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.tokens.cmp(&b.tokens))
+    });
+
+    // Drop single words that only ever appear inside a stronger phrase
+    let mut chosen: Vec<Keyword> = Vec::new();
+    for keyword in keywords {
+        if chosen.len() >= limit {
+            break;
+        }
+        let covered = keyword.tokens.len() == 1
+            && chosen.iter().any(|c| {
+                c.tokens.len() > 1
+                    && c.tokens.contains(&keyword.tokens[0])
+                    && c.weight >= keyword.weight
+            });
+        if !covered {
+            chosen.push(keyword);
+        }
+    }
+    chosen
+}
+
+/// Where in the resume a keyword was found.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    Skill {
+        section: String,
+    },
+    Highlight {
+        position: String,
+        company: String,
+        index: usize,
+    },
+    Project {
+        title: String,
+    },
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Skill { section } => {
+                write!(f, "skills ({})", section.trim().trim_end_matches(':'))
+            }
+            Source::Highlight {
+                position,
+                company,
+                index,
+            } => write!(f, "{}, {} highlight #{}", position, company, index + 1),
+            Source::Project { title } => write!(f, "project \"{}\"", title),
+        }
+    }
+}
+
+/// Every piece of resume text a keyword can be matched against.
+pub fn sources(resume: &Resume) -> Vec<(Source, Vec<String>)> {
+    let mut out = Vec::new();
+    for skill in &resume.skills {
+        for s in &skill.skills {
+            out.push((
+                Source::Skill {
+                    section: skill.section.clone(),
+                },
+                tokenize(s),
+            ));
+        }
+    }
+    for job in &resume.employment {
+        for (index, highlight) in job.highlights.iter().enumerate() {
+            out.push((
+                Source::Highlight {
+                    position: job.position.clone(),
+                    company: job.company.clone(),
+                    index,
+                },
+                tokenize(highlight),
+            ));
+        }
+    }
+    for proj in &resume.projects {
+        let mut text = proj.title.clone();
+        if let Some(nickname) = &proj.nickname {
+            text = format!("{} {}", nickname, text);
+        }
+        if let Some(description) = &proj.description {
+            text = format!("{} {}", text, description);
+        }
+        out.push((
+            Source::Project {
+                title: proj.title.clone(),
+            },
+            tokenize(&text),
+        ));
+    }
+    out
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hit {
+    pub keyword: Keyword,
+    pub sources: Vec<Source>,
+}
+
+/// How well a resume covers a job posting's keywords.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchReport {
+    /// The weighted share of keywords the resume covers, from 0 to 100.
+    pub percentage: f32,
+    pub covered: Vec<Hit>,
+    pub missing: Vec<Keyword>,
+}
+
+pub fn score(resume: &Resume, job: &str) -> MatchReport {
+    let keywords = extract(job, resume, 30);
+    let sources = sources(resume);
+
+    let mut covered = Vec::new();
+    let mut missing = Vec::new();
+    for keyword in keywords {
+        let hits: Vec<Source> = sources
+            .iter()
+            .filter(|(_, tokens)| count_phrase(tokens, &keyword.tokens) > 0)
+            .map(|(source, _)| source.clone())
+            .collect();
+        if hits.is_empty() {
+            missing.push(keyword);
+        } else {
+            covered.push(Hit {
+                keyword,
+                sources: hits,
+            });
+        }
+    }
+
+    let total: f32 = covered.iter().map(|h| h.keyword.weight).sum::<f32>()
+        + missing.iter().map(|k| k.weight).sum::<f32>();
+    let percentage = if total > 0.0 {
+        100.0 * covered.iter().map(|h| h.keyword.weight).sum::<f32>() / total
+    } else {
+        0.0
+    };
+
+    MatchReport {
+        percentage,
+        covered,
+        missing,
+    }
+}
+
+/// Scores each document by the TF-IDF cosine similarity of its terms to the
+/// query's, with document frequencies taken from `documents` themselves.
+/// Documents sharing no terms with the query score zero.
+pub fn rank(documents: &[&str], query: &str) -> Vec<f32> {
+    let docs: Vec<Vec<String>> = documents
+        .iter()
+        .map(|d| {
+            tokenize(d)
+                .into_iter()
+                .filter(|t| !is_stopword(t))
+                .collect()
+        })
+        .collect();
+    let query: Vec<String> = tokenize(query)
+        .into_iter()
+        .filter(|t| !is_stopword(t))
+        .collect();
+
+    let n = docs.len() as f32;
+    let mut df: BTreeMap<&str, f32> = BTreeMap::new();
+    for doc in &docs {
+        let mut seen: Vec<&str> = doc.iter().map(|t| t.as_str()).collect();
+        seen.sort_unstable();
+        seen.dedup();
+        for t in seen {
+            *df.entry(t).or_default() += 1.0;
+        }
+    }
+    let idf = |t: &str| ((n + 1.0) / (df.get(t).copied().unwrap_or(0.0) + 1.0)).ln() + 1.0;
+
+    let vector = |tokens: &[String]| -> BTreeMap<String, f32> {
+        let mut v: BTreeMap<String, f32> = BTreeMap::new();
+        for t in tokens {
+            *v.entry(t.clone()).or_default() += 1.0;
+        }
+        for (t, w) in v.iter_mut() {
+            *w *= idf(t);
+        }
+        v
+    };
+    let norm = |v: &BTreeMap<String, f32>| v.values().map(|w| w * w).sum::<f32>().sqrt();
+
+    let q = vector(&query);
+    let q_norm = norm(&q);
+    docs.iter()
+        .map(|doc| {
+            let d = vector(doc);
+            let d_norm = norm(&d);
+            if q_norm == 0.0 || d_norm == 0.0 {
+                return 0.0;
+            }
+            let dot: f32 = d
+                .iter()
+                .filter_map(|(t, w)| q.get(t).map(|qw| w * qw))
+                .sum();
+            dot / (q_norm * d_norm)
+        })
+        .collect()
+}
+
+/// Reorders each group from most to least relevant to `query`, keeping the
+/// original order among equally relevant items. Every group is ranked
+/// against the same corpus so the weights are comparable between them.
+fn sort_by_relevance<'a>(groups: impl Iterator<Item = &'a mut Vec<String>>, query: &str) {
+    let mut groups: Vec<&mut Vec<String>> = groups.collect();
+    let documents: Vec<&str> = groups
+        .iter()
+        .flat_map(|g| g.iter().map(|s| s.as_str()))
+        .collect();
+    let mut scores = rank(&documents, query).into_iter();
+
+    for group in groups.iter_mut() {
+        let mut scored: Vec<(f32, String)> = group
+            .drain(..)
+            .map(|item| (scores.next().unwrap_or(0.0), item))
+            .collect();
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        group.extend(scored.into_iter().map(|(_, item)| item));
+    }
+}
+
+/// Tailors a resume to `query` (the position, plus the job posting if there
+/// is one): each job's highlights and each skill section's skills are put in
+/// order of relevance, and each job keeps at most `max_highlights` highlights.
+pub fn tailor(resume: &mut Resume, query: &str, max_highlights: Option<usize>) {
+    sort_by_relevance(
+        resume.employment.iter_mut().map(|j| &mut j.highlights),
+        query,
+    );
+    if let Some(max) = max_highlights {
+        for job in resume.employment.iter_mut() {
+            job.highlights.truncate(max);
+        }
+    }
+    sort_by_relevance(resume.skills.iter_mut().map(|s| &mut s.skills), query);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::utils::{Skill, WorkExperience};
+
+    fn resume() -> Resume {
+        Resume {
+            skills: vec![Skill {
+                section: "Languages: ".to_string(),
+                skills: vec!["Rust".to_string(), "Go".to_string()],
+            }],
+            employment: vec![WorkExperience {
+                position: "Software Engineer".to_string(),
+                company: "Acme".to_string(),
+                location: "Remote".to_string(),
+                start_date: "2020".to_string(),
+                end_date: "Present".to_string(),
+                highlights: vec!["Ran Kubernetes clusters for the payments platform".to_string()],
+            }],
+            ..Resume::default()
+        }
+    }
+
+    #[test]
+    fn words_keep_technology_names_whole() {
+        assert_eq!(
+            words("C++, C#, Node.js and CI/CD; 5+ years of 2020-era code."),
+            vec!["c++", "c#", "node.js", "and", "ci/cd", "years", "of", "2020-era", "code"]
+        );
+    }
+
+    #[test]
+    fn stem_only_strips_plural_s() {
+        assert_eq!(stem("pipelines"), "pipeline");
+        assert_eq!(stem("apis"), "api");
+        assert_eq!(stem("class"), "class");
+        assert_eq!(stem("bus"), "bus");
+        assert_eq!(stem("k8s"), "k8s");
+        assert_eq!(tokenize("Data Pipelines"), vec!["data", "pipeline"]);
+    }
+
+    #[test]
+    fn posting_filler_is_ignored() {
+        for word in [
+            "need",
+            "want",
+            "make",
+            "get",
+            "across-team",
+            "day-to-day",
+            "hands-on",
+        ] {
+            assert!(is_stopword(word), "{} isn't a stopword", word);
+        }
+        for word in ["rust", "ci/cd", "cross-functional", "node.js"] {
+            assert!(!is_stopword(word), "{} is a stopword", word);
+        }
+    }
+
+    #[test]
+    fn extract_boosts_repeated_phrases_over_their_words() {
+        let job = "We use machine learning. Machine learning at scale.";
+        let keywords = extract(job, &Resume::default(), 10);
+        let terms: Vec<&str> = keywords.iter().map(|k| k.term.as_str()).collect();
+        // `machine` and `learning` only appear inside the phrase
+        assert_eq!(terms, vec!["machine learning", "scale"]);
+        assert_eq!(keywords[0].tokens, vec!["machine", "learning"]);
+        assert_eq!(keywords[0].weight, 3.0);
+    }
+
+    #[test]
+    fn extract_boosts_the_resume_skills() {
+        let keywords = extract("Python or Rust, then Python again.", &resume(), 10);
+        assert_eq!(keywords[0].term, "rust");
+        assert_eq!(keywords[0].weight, 3.0);
+        assert_eq!(keywords[1].term, "python");
+        assert_eq!(keywords[1].weight, 2.0);
+    }
+
+    #[test]
+    fn extract_stops_at_the_limit() {
+        let keywords = extract("Rust Go Python Kubernetes Terraform", &Resume::default(), 2);
+        assert_eq!(keywords.len(), 2);
+    }
+
+    #[test]
+    fn score_weighs_covered_and_missing_keywords() {
+        let report = score(&resume(), "We need Rust and Kubernetes. Python preferred.");
+        let covered: Vec<&str> = report
+            .covered
+            .iter()
+            .map(|h| h.keyword.term.as_str())
+            .collect();
+        let missing: Vec<&str> = report.missing.iter().map(|k| k.term.as_str()).collect();
+        assert_eq!(covered, vec!["rust", "kubernetes"]);
+        assert_eq!(missing, vec!["python"]);
+        // Rust weighs 3 as one of the skills; Kubernetes and Python 1 each
+        assert_eq!(report.percentage, 80.0);
+
+        assert_eq!(
+            report.covered[0].sources,
+            vec![Source::Skill {
+                section: "Languages: ".to_string()
+            }]
+        );
+        assert_eq!(
+            report.covered[1].sources,
+            vec![Source::Highlight {
+                position: "Software Engineer".to_string(),
+                company: "Acme".to_string(),
+                index: 0
+            }]
+        );
+        assert_eq!(
+            report.covered[1].sources[0].to_string(),
+            "Software Engineer, Acme highlight #1"
+        );
+    }
+
+    #[test]
+    fn score_is_zero_without_keywords() {
+        assert_eq!(
+            score(&resume(), "We need you to join our team.").percentage,
+            0.0
+        );
+    }
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn rank_prefers_focused_matches() {
+        let scores = rank(
+            &[
+                "Built Rust services",
+                "Managed a Go team",
+                "Wrote Rust and Go tooling",
+            ],
+            "Rust",
+        );
+        assert!(scores[0] > scores[2], "{:?}", scores);
+        assert!(scores[2] > 0.0, "{:?}", scores);
+        assert_eq!(scores[1], 0.0);
+    }
+
+    #[test]
+    fn rank_scores_nothing_against_an_empty_query() {
+        assert_eq!(
+            rank(&["Built Rust services", "Ran Go"], "the team"),
+            vec![0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn sort_by_relevance_keeps_ties_in_order() {
+        let mut group = strings(&["Plan meetings", "Rust CLI", "Write docs", "Rust service"]);
+        sort_by_relevance(std::iter::once(&mut group), "Rust");
+        assert_eq!(
+            group,
+            strings(&["Rust CLI", "Rust service", "Plan meetings", "Write docs"])
+        );
+    }
+
+    fn tailorable() -> Resume {
+        let job = |company: &str, highlights: &[&str]| WorkExperience {
+            position: "Engineer".to_string(),
+            company: company.to_string(),
+            location: "Remote".to_string(),
+            start_date: "2020".to_string(),
+            end_date: "Present".to_string(),
+            highlights: strings(highlights),
+        };
+        Resume {
+            skills: vec![
+                Skill {
+                    section: "Tools: ".to_string(),
+                    skills: strings(&["Python", "Terraform", "Go", "Kubernetes"]),
+                },
+                Skill {
+                    section: "Other: ".to_string(),
+                    skills: strings(&["Public speaking", "Kubernetes operators"]),
+                },
+            ],
+            employment: vec![
+                job(
+                    "Acme",
+                    &[
+                        "Led hiring for the data group",
+                        "Ran Kubernetes clusters",
+                        "Wrote Terraform modules for Kubernetes",
+                        "Organised offsites",
+                    ],
+                ),
+                job("Initech", &["Kept the printers running"]),
+            ],
+            ..Resume::default()
+        }
+    }
+
+    #[test]
+    fn tailor_orders_highlights_and_skills() {
+        let mut resume = tailorable();
+        tailor(
+            &mut resume,
+            "Platform Engineer\nKubernetes and Terraform",
+            None,
+        );
+        assert_eq!(
+            resume.employment[0].highlights,
+            strings(&[
+                "Wrote Terraform modules for Kubernetes",
+                "Ran Kubernetes clusters",
+                "Led hiring for the data group",
+                "Organised offsites",
+            ])
+        );
+        assert_eq!(
+            resume.employment[1].highlights,
+            strings(&["Kept the printers running"])
+        );
+        assert_eq!(
+            resume.skills[0].skills,
+            strings(&["Terraform", "Kubernetes", "Python", "Go"])
+        );
+        assert_eq!(
+            resume.skills[1].skills,
+            strings(&["Kubernetes operators", "Public speaking"])
+        );
+    }
+
+    #[test]
+    fn tailor_keeps_the_most_relevant_highlights() {
+        let mut resume = tailorable();
+        tailor(&mut resume, "Kubernetes and Terraform", Some(2));
+        assert_eq!(
+            resume.employment[0].highlights,
+            strings(&[
+                "Wrote Terraform modules for Kubernetes",
+                "Ran Kubernetes clusters",
+            ])
+        );
+        assert_eq!(resume.employment[1].highlights.len(), 1);
+    }
+}
@@ -1,19 +1,44 @@
 pub mod builders;
 pub mod commands;
 pub mod config;
+pub mod generate;
 pub mod helpers;
+pub mod job;
+pub mod keywords;
 
 use color_print::cstr;
-pub static CONFIG_NAME: &'static str = cstr!(
+pub static CONFIG_NAME: &str = cstr!(
     r#"<bold><bright-white>Your name hasn't been set, try running the config:</></>
 
   <white><dim>$</></> <bold><cyan>cv config</> <green>name</> <yellow>'<<YOUR NAME>>'</></>
 "#
 );
 
-pub static CONFIG_API: &'static str = cstr!(
+pub static CONFIG_API: &str = cstr!(
     r#"<bold><bright-white>Your api key hasn't been set, try running the config:</></>
 
   <white><dim>$</></> <bold><cyan>cv config</> <green>api</> <yellow>'<<YOUR OPENAI API KEY>>'</></>
 "#
 );
+
+pub static CONFIG_RESUME: &str = cstr!(
+    r#"<bold><bright-white>Your resume hasn't been set, add a resume block to your config:</></>
+
+  <white><dim>$</></> <bold><cyan>$EDITOR</> <yellow>~/.cv.config.yaml</></>
+
+  <green>resume:</>
+    <green>summary:</> <yellow>"Experienced **software engineer** with ..."</>
+    <green>skills:</>
+      - <green>section:</> <yellow>"Languages: "</>
+        <green>skills:</> [<yellow>Rust</>, <yellow>Python</>]
+    <green>employment:</>
+      - <green>position:</> <yellow>Software Engineer</>
+        <green>company:</> <yellow>Acme</>
+        <green>location:</> <yellow>Remote</>
+        <green>start_date:</> <yellow>Jan 2020</>
+        <green>end_date:</> <yellow>Present</>
+        <green>highlights:</> [<yellow>"Shipped things"</>]
+    <green>education:</> []
+    <green>projects:</> []
+"#
+);
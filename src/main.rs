@@ -1,10 +1,11 @@
 use clap::{
     builder::{
         styling::{AnsiColor, Effects},
-        NonEmptyStringValueParser, PathBufValueParser, Styles, ValueParser,
+        NonEmptyStringValueParser, PathBufValueParser, PossibleValuesParser, RangedU64ValueParser,
+        Styles,
     },
     error::{Error as ClapError, Result},
-    Arg, ColorChoice, Command, ValueHint,
+    value_parser, Arg, ArgAction, ArgGroup, ColorChoice, Command, ValueHint,
 };
 
 #[tokio::main]
@@ -47,6 +48,57 @@ async fn main() -> Result<(), ClapError> {
                 .value_name("POSITION")
                 .required(false)
                 .help("The position you're applying to (leaving this blank will output a standard cover letter for any position)"))
+            .arg(Arg::new("hiring-manager")
+                .short('m')
+                .long("hiring-manager")
+                .value_name("HIRING MANAGER")
+                .required(false)
+                .help("Who to address the letter to (defaults to the company)"))
+            .arg(Arg::new("template")
+                .short('t')
+                .long("template")
+                .value_name("TEMPLATE")
+                .value_hint(ValueHint::FilePath)
+                .value_parser(PathBufValueParser::new())
+                .required(false)
+                .help("A template file for the letter body. Supports {{company}}, {{position}}, {{location}}, {{hiring_manager}}, {{name}}, {{date}} and {{#if position}}...{{else}}...{{/if}} blocks"))
+            .arg(Arg::new("generate")
+                .short('g')
+                .long("generate")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("template")
+                .help("Have the configured model write the letter body from your resume"))
+            .arg(Arg::new("job-description")
+                .short('j')
+                .long("job-description")
+                .value_name("TEXT")
+                .requires("generate")
+                .conflicts_with_all(["job-file", "job-url"])
+                .required(false)
+                .help("The job posting to tailor the generated letter to"))
+            .arg(Arg::new("job-file")
+                .long("job-file")
+                .value_name("FILE")
+                .value_hint(ValueHint::FilePath)
+                .requires("generate")
+                .conflicts_with("job-url")
+                .required(false)
+                .help("Read the job posting from a text or HTML file ('-' reads stdin)"))
+            .arg(Arg::new("job-url")
+                .long("job-url")
+                .value_name("URL")
+                .value_hint(ValueHint::Url)
+                .requires("generate")
+                .required(false)
+                .help("Download the job posting from a web page"))
+            .arg(Arg::new("variant")
+                .long("variant")
+                .short('v')
+                .value_name("VARIANT")
+                .value_parser(NonEmptyStringValueParser::new())
+                .requires("generate")
+                .required(false)
+                .help("Generate the letter from a named variant from the resume's `variants:` block"))
             .arg(Arg::new("output")
                 .value_name("OUTPUT")
                 .help("The file to save the cover letter to.")
@@ -56,9 +108,39 @@ async fn main() -> Result<(), ClapError> {
             .arg(Arg::new("name")
                 .value_name("NAME")
                 .short('n')
-                .value_parser(ValueParser::new(coverletter::helpers::NameValueParser))
+                .value_parser(NonEmptyStringValueParser::new())
                 .long("name")
-                .help("Your name for the cover letter. (This will be saved at ~/.coverletter so you only need to provide it once)")))
+                .help("Your name for the cover letter. (This will be saved at ~/.coverletter so you only need to provide it once)"))
+            .arg(Arg::new("theme")
+                .long("theme")
+                .value_name("THEME")
+                .value_hint(ValueHint::FilePath)
+                .value_parser(NonEmptyStringValueParser::new())
+                .required(false)
+                .help("A built-in theme (classic, slate, forest, mono) or a theme file. Overrides `theme:` in ~/.cv.config.yaml"))
+            .arg(Arg::new("paper")
+                .long("paper")
+                .value_name("SIZE")
+                .value_parser(|s: &str| s.parse::<coverletter::builders::paper::Paper>())
+                .required(false)
+                .help("letter, a4, legal or <width>x<height> in mm. Defaults to `paper:` in ~/.cv.config.yaml, then your locale"))
+            .arg(Arg::new("title")
+                .long("title")
+                .value_name("TITLE")
+                .value_parser(NonEmptyStringValueParser::new())
+                .required(false)
+                .help("The title in the PDF's metadata (defaults to \"<name> - Cover Letter for <company>\")"))
+            .arg(Arg::new("format")
+                .long("format")
+                .short('f')
+                .value_name("FORMAT")
+                .value_parser(PossibleValuesParser::new(coverletter::builders::format::Format::NAMES))
+                .required(false)
+                .help("The kind of file to write. Defaults to the output file's extension, then pdf"))
+            .arg(Arg::new("optimize")
+                .long("optimize")
+                .action(ArgAction::SetTrue)
+                .help("Shrink the PDF with Ghostscript, if it's installed. Without it, the PDF is written as rendered")))
         .subcommand(Command::new("resume")
             .about("Build a resume for an application")
             .arg(Arg::new("position")
@@ -69,6 +151,13 @@ async fn main() -> Result<(), ClapError> {
                 .value_name("POSITION")
                 .required(true)
                 .help("The position you're applying to."))
+            .arg(Arg::new("company")
+                .short('c')
+                .long("company")
+                .value_name("COMPANY")
+                .value_parser(NonEmptyStringValueParser::new())
+                .required(false)
+                .help("The company you're applying to, for the PDF's metadata"))
             .arg(Arg::new("output")
                 .value_name("OUTPUT")
                 .help("The file to save the resume to.")
@@ -78,9 +167,234 @@ async fn main() -> Result<(), ClapError> {
             .arg(Arg::new("name")
                 .value_name("NAME")
                 .short('n')
-                .value_parser(ValueParser::new(coverletter::helpers::NameValueParser))
+                .value_parser(NonEmptyStringValueParser::new())
                 .long("name")
-                .help("Your name for the resume.")))
+                .help("Your name for the resume."))
+            .arg(Arg::new("theme")
+                .long("theme")
+                .value_name("THEME")
+                .value_hint(ValueHint::FilePath)
+                .value_parser(NonEmptyStringValueParser::new())
+                .required(false)
+                .help("A built-in theme (classic, slate, forest, mono) or a theme file. Overrides `theme:` in ~/.cv.config.yaml"))
+            .arg(Arg::new("paper")
+                .long("paper")
+                .value_name("SIZE")
+                .value_parser(|s: &str| s.parse::<coverletter::builders::paper::Paper>())
+                .required(false)
+                .help("letter, a4, legal or <width>x<height> in mm. Defaults to `paper:` in ~/.cv.config.yaml, then your locale"))
+            .arg(Arg::new("title")
+                .long("title")
+                .value_name("TITLE")
+                .value_parser(NonEmptyStringValueParser::new())
+                .required(false)
+                .help("The title in the PDF's metadata (defaults to \"<name> - Resume\")"))
+            .arg(Arg::new("format")
+                .long("format")
+                .short('f')
+                .value_name("FORMAT")
+                .value_parser(PossibleValuesParser::new(coverletter::builders::format::Format::NAMES))
+                .required(false)
+                .help("The kind of file to write. Defaults to the output file's extension, then pdf"))
+            .arg(Arg::new("optimize")
+                .long("optimize")
+                .action(ArgAction::SetTrue)
+                .help("Shrink the PDF with Ghostscript, if it's installed. Without it, the PDF is written as rendered"))
+            .arg(Arg::new("variant")
+                .long("variant")
+                .short('v')
+                .value_name("VARIANT")
+                .value_parser(NonEmptyStringValueParser::new())
+                .required(false)
+                .help("Build a named variant from the resume's `variants:` block"))
+            .arg(Arg::new("layout")
+                .long("layout")
+                .value_name("LAYOUT")
+                .value_parser(PossibleValuesParser::new(coverletter::builders::resume::ResumeLayout::NAMES))
+                .required(false)
+                .help("How to arrange the sections: classic, sidebar (skills and education in a side column) or compact"))
+            .arg(Arg::new("max-highlights")
+                .long("max-highlights")
+                .value_name("N")
+                .value_parser(RangedU64ValueParser::<usize>::new().range(1..))
+                .required(false)
+                .help("Keep only the N highlights per job most relevant to the position and job posting"))
+            .arg(Arg::new("fit-pages")
+                .long("fit-pages")
+                .value_name("PAGES")
+                .value_parser(value_parser!(u64).range(1..))
+                .required(false)
+                .help("Tighten the line spacing, font sizes and margins until the resume fits on PAGES pages"))
+            .arg(Arg::new("trim-highlights")
+                .long("trim-highlights")
+                .action(ArgAction::SetTrue)
+                .requires("fit-pages")
+                .help("Let --fit-pages drop the least relevant job highlights if tightening isn't enough"))
+            .arg(Arg::new("job-description")
+                .short('j')
+                .long("job-description")
+                .value_name("TEXT")
+                .conflicts_with_all(["job-file", "job-url"])
+                .required(false)
+                .help("The job posting to rank highlights and skills against"))
+            .arg(Arg::new("job-file")
+                .long("job-file")
+                .value_name("FILE")
+                .value_hint(ValueHint::FilePath)
+                .conflicts_with("job-url")
+                .required(false)
+                .help("Read the job posting from a text or HTML file ('-' reads stdin)"))
+            .arg(Arg::new("job-url")
+                .long("job-url")
+                .value_name("URL")
+                .value_hint(ValueHint::Url)
+                .required(false)
+                .help("Download the job posting from a web page")))
+        .subcommand(Command::new("packet")
+            .about("Build the cover letter and resume as one PDF, with a bookmark for each")
+            .arg(Arg::new("company")
+                .short('c')
+                .long("company")
+                .value_name("COMPANY")
+                .value_parser(NonEmptyStringValueParser::new())
+                .help("The company you're applying to")
+                .required(true))
+            .arg(Arg::new("position")
+                .short('p')
+                .long("position")
+                .visible_short_alias('r')
+                .visible_alias("role")
+                .value_name("POSITION")
+                .value_parser(NonEmptyStringValueParser::new())
+                .required(true)
+                .help("The position you're applying to"))
+            .arg(Arg::new("location")
+                .short('l')
+                .long("location")
+                .value_name("LOCATION")
+                .required(false)
+                .help("The location of the office you're applying to"))
+            .arg(Arg::new("hiring-manager")
+                .short('m')
+                .long("hiring-manager")
+                .value_name("HIRING MANAGER")
+                .required(false)
+                .help("Who to address the letter to (defaults to the company)"))
+            .arg(Arg::new("template")
+                .short('t')
+                .long("template")
+                .value_name("TEMPLATE")
+                .value_hint(ValueHint::FilePath)
+                .value_parser(PathBufValueParser::new())
+                .required(false)
+                .help("A template file for the letter body, as for `cv cover`"))
+            .arg(Arg::new("generate")
+                .short('g')
+                .long("generate")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("template")
+                .help("Have the configured model write the letter body from your resume"))
+            .arg(Arg::new("job-description")
+                .short('j')
+                .long("job-description")
+                .value_name("TEXT")
+                .conflicts_with_all(["job-file", "job-url"])
+                .required(false)
+                .help("The job posting to tailor the resume and any generated letter to"))
+            .arg(Arg::new("job-file")
+                .long("job-file")
+                .value_name("FILE")
+                .value_hint(ValueHint::FilePath)
+                .conflicts_with("job-url")
+                .required(false)
+                .help("Read the job posting from a text or HTML file ('-' reads stdin)"))
+            .arg(Arg::new("job-url")
+                .long("job-url")
+                .value_name("URL")
+                .value_hint(ValueHint::Url)
+                .required(false)
+                .help("Download the job posting from a web page"))
+            .arg(Arg::new("output")
+                .value_name("OUTPUT")
+                .help("The PDF to save the packet to.")
+                .value_hint(ValueHint::FilePath)
+                .value_parser(PathBufValueParser::new())
+                .required(true))
+            .arg(Arg::new("name")
+                .value_name("NAME")
+                .short('n')
+                .value_parser(NonEmptyStringValueParser::new())
+                .long("name")
+                .help("Your name for the packet."))
+            .arg(Arg::new("theme")
+                .long("theme")
+                .value_name("THEME")
+                .value_hint(ValueHint::FilePath)
+                .value_parser(NonEmptyStringValueParser::new())
+                .required(false)
+                .help("A built-in theme (classic, slate, forest, mono) or a theme file. Overrides `theme:` in ~/.cv.config.yaml"))
+            .arg(Arg::new("paper")
+                .long("paper")
+                .value_name("SIZE")
+                .value_parser(|s: &str| s.parse::<coverletter::builders::paper::Paper>())
+                .required(false)
+                .help("letter, a4, legal or <width>x<height> in mm. Defaults to `paper:` in ~/.cv.config.yaml, then your locale"))
+            .arg(Arg::new("title")
+                .long("title")
+                .value_name("TITLE")
+                .value_parser(NonEmptyStringValueParser::new())
+                .required(false)
+                .help("The title in the PDF's metadata (defaults to \"<name> - Application to <company>\")"))
+            .arg(Arg::new("optimize")
+                .long("optimize")
+                .action(ArgAction::SetTrue)
+                .help("Shrink the PDF with Ghostscript, if it's installed. Without it, the PDF is written as rendered"))
+            .arg(Arg::new("variant")
+                .long("variant")
+                .short('v')
+                .value_name("VARIANT")
+                .value_parser(NonEmptyStringValueParser::new())
+                .required(false)
+                .help("Build a named variant from the resume's `variants:` block"))
+            .arg(Arg::new("layout")
+                .long("layout")
+                .value_name("LAYOUT")
+                .value_parser(PossibleValuesParser::new(coverletter::builders::resume::ResumeLayout::NAMES))
+                .required(false)
+                .help("How to arrange the resume's sections: classic, sidebar or compact"))
+            .arg(Arg::new("max-highlights")
+                .long("max-highlights")
+                .value_name("N")
+                .value_parser(RangedU64ValueParser::<usize>::new().range(1..))
+                .required(false)
+                .help("Keep only the N highlights per job most relevant to the position and job posting")))
+        .subcommand(Command::new("score")
+            .about("Score how well your resume matches a job posting's keywords")
+            .group(ArgGroup::new("job")
+                .args(["job-description", "job-file", "job-url"])
+                .required(true))
+            .arg(Arg::new("job-description")
+                .short('j')
+                .long("job-description")
+                .value_name("TEXT")
+                .help("The job posting text"))
+            .arg(Arg::new("job-file")
+                .long("job-file")
+                .value_name("FILE")
+                .value_hint(ValueHint::FilePath)
+                .help("Read the job posting from a text or HTML file ('-' reads stdin)"))
+            .arg(Arg::new("job-url")
+                .long("job-url")
+                .value_name("URL")
+                .value_hint(ValueHint::Url)
+                .help("Download the job posting from a web page"))
+            .arg(Arg::new("variant")
+                .long("variant")
+                .short('v')
+                .value_name("VARIANT")
+                .value_parser(NonEmptyStringValueParser::new())
+                .required(false)
+                .help("Score a named variant from the resume's `variants:` block")))
         .subcommand(Command::new("config")
             .about("Configure the settings")
             .subcommand(Command::new("name")
@@ -96,16 +410,55 @@ async fn main() -> Result<(), ClapError> {
                      .value_name("API-KEY")
                      .value_parser(NonEmptyStringValueParser::new())
                      .help("Your OpenAI API key from https://platform.openai.com/account/api-keys")
-                     .required(true))));
+                     .required(true)))
+            .subcommand(Command::new("contact")
+                .about("Edit the contact details shown on your documents")
+                .arg_required_else_help(true)
+                .subcommand(Command::new("add")
+                    .about("Add a contact item")
+                    .arg(Arg::new("kind")
+                         .value_name("KIND")
+                         .value_parser(PossibleValuesParser::new(coverletter::builders::utils::ContactKind::NAMES))
+                         .help("The type of contact item")
+                         .required(true))
+                    .arg(Arg::new("label")
+                         .value_name("LABEL")
+                         .value_parser(NonEmptyStringValueParser::new())
+                         .help("The text shown on your documents, e.g. 'github.com/octocat'")
+                         .required(true))
+                    .arg(Arg::new("url")
+                         .long("url")
+                         .short('u')
+                         .value_name("URL")
+                         .value_parser(NonEmptyStringValueParser::new())
+                         .help("Where the item links to, e.g. 'https://github.com/octocat'")
+                         .required(false)))
+                .subcommand(Command::new("remove")
+                    .about("Remove a contact item")
+                    .arg(Arg::new("item")
+                         .value_name("ITEM")
+                         .value_parser(NonEmptyStringValueParser::new())
+                         .help("The number shown by `cv config contact list`, or the item's label")
+                         .required(true)))
+                .subcommand(Command::new("list")
+                    .about("List your contact items"))));
 
     let matches = cmd.clone().get_matches();
 
     match matches.subcommand() {
         Some(("cover", sub_matches)) => {
-            coverletter::commands::coverletter(cmd.clone(), sub_matches)?
+            coverletter::commands::coverletter(cmd.clone(), sub_matches).await?
         }
         Some(("config", sub_matches)) => coverletter::commands::config(cmd.clone(), sub_matches)?,
-        Some(("resume", sub_matches)) => coverletter::commands::resume(cmd.clone(), sub_matches)?,
+        Some(("resume", sub_matches)) => {
+            coverletter::commands::resume(cmd.clone(), sub_matches).await?
+        }
+        Some(("packet", sub_matches)) => {
+            coverletter::commands::packet(cmd.clone(), sub_matches).await?
+        }
+        Some(("score", sub_matches)) => {
+            coverletter::commands::score(cmd.clone(), sub_matches).await?
+        }
         Some((sub, _)) => {
             return Err(coverletter::helpers::unable_to_parse_subcommand(
                 cmd,
@@ -115,5 +468,6 @@ async fn main() -> Result<(), ClapError> {
         None => return Err(coverletter::helpers::unable_to_parse_subcommand(cmd, None)),
     };
 
-    Ok(cmd.build())
+    cmd.build();
+    Ok(())
 }
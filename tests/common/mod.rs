@@ -0,0 +1,172 @@
+// Shared by several test binaries, each of which uses only part of it
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// What the fixture server was sent.
+#[derive(Debug)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    /// Keyed by the lowercased header name.
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+/// Serves a single HTTP response on a free port of 127.0.0.1. Returns the
+/// server's base URL and a handle that resolves to the request it received.
+pub async fn serve_once(
+    status: &'static str,
+    content_type: &'static str,
+    body: &'static str,
+) -> (String, JoinHandle<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut data = Vec::new();
+        let mut buf = [0u8; 4096];
+        let head_end = loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            assert!(n > 0, "the client hung up before sending its headers");
+            data.extend_from_slice(&buf[..n]);
+            if let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+                break end + 4;
+            }
+        };
+
+        let head = String::from_utf8_lossy(&data[..head_end]).to_string();
+        let mut lines = head.split("\r\n");
+        let mut request_line = lines.next().unwrap().split(' ');
+        let method = request_line.next().unwrap().to_string();
+        let path = request_line.next().unwrap().to_string();
+        let headers: HashMap<String, String> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+            .collect();
+
+        let length: usize = headers
+            .get("content-length")
+            .and_then(|length| length.parse().ok())
+            .unwrap_or(0);
+        while data.len() < head_end + length {
+            let n = stream.read(&mut buf).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            data.extend_from_slice(&buf[..n]);
+        }
+        let received = String::from_utf8_lossy(&data[head_end..]).to_string();
+
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            content_type,
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+        stream.shutdown().await.unwrap();
+        Request {
+            method,
+            path,
+            headers,
+            body: received,
+        }
+    });
+    (base, handle)
+}
+
+/// The text of each page of `pdf`, one line per text object. The fonts are
+/// embedded with Identity-H encoding, which lopdf's `extract_text` can't
+/// read, so the glyphs are mapped back through each font's ToUnicode CMap.
+pub fn page_text(pdf: &[u8]) -> Vec<String> {
+    let doc = lopdf::Document::load_mem(pdf).expect("the output parses");
+    doc.get_pages()
+        .into_values()
+        .map(|page| {
+            // lopdf's `get_page_fonts` doesn't follow a referenced /Resources
+            let resolve = |object| resolve(&doc, object);
+            let fonts: HashMap<Vec<u8>, HashMap<u16, String>> = doc
+                .get_dictionary(page)
+                .and_then(|page| page.get(b"Resources"))
+                .ok()
+                .and_then(resolve)
+                .and_then(|resources| resources.get(b"Font").ok())
+                .and_then(resolve)
+                .into_iter()
+                .flat_map(|fonts| fonts.iter())
+                .filter_map(|(name, font)| Some((name.clone(), to_unicode(&doc, resolve(font)?))))
+                .collect();
+            let content = doc.get_and_decode_page_content(page).unwrap();
+            let mut lines: Vec<String> = Vec::new();
+            let mut line = String::new();
+            let mut font: Option<&HashMap<u16, String>> = None;
+            for op in &content.operations {
+                match op.operator.as_str() {
+                    "Tf" => font = op.operands[0].as_name().ok().and_then(|n| fonts.get(n)),
+                    "Tj" | "TJ" => {
+                        let strings = match op.operands[0].as_array() {
+                            Ok(items) => items.iter().collect(),
+                            Err(_) => vec![&op.operands[0]],
+                        };
+                        for bytes in strings.iter().filter_map(|s| s.as_str().ok()) {
+                            for code in bytes.chunks(2) {
+                                let code =
+                                    u16::from_be_bytes([code[0], *code.get(1).unwrap_or(&0)]);
+                                if let Some(text) = font.and_then(|f| f.get(&code)) {
+                                    line.push_str(text);
+                                }
+                            }
+                        }
+                    }
+                    "ET" => lines.push(std::mem::take(&mut line)),
+                    _ => {}
+                }
+            }
+            lines.join("\n")
+        })
+        .collect()
+}
+
+/// `object`, or the dictionary it refers to.
+fn resolve<'a>(
+    doc: &'a lopdf::Document,
+    object: &'a lopdf::Object,
+) -> Option<&'a lopdf::Dictionary> {
+    match object {
+        lopdf::Object::Reference(id) => doc.get_dictionary(*id).ok(),
+        object => object.as_dict().ok(),
+    }
+}
+
+/// The glyph to text map in a font's ToUnicode CMap.
+fn to_unicode(doc: &lopdf::Document, font: &lopdf::Dictionary) -> HashMap<u16, String> {
+    let mut map = HashMap::new();
+    let Ok(stream) = font
+        .get(b"ToUnicode")
+        .and_then(lopdf::Object::as_reference)
+        .and_then(|id| doc.get_object(id))
+        .and_then(lopdf::Object::as_stream)
+    else {
+        return map;
+    };
+    let content = stream
+        .decompressed_content()
+        .unwrap_or_else(|_| stream.content.clone());
+    let hex = |s: &str| u32::from_str_radix(s.trim_matches(|c| c == '<' || c == '>'), 16).ok();
+    for line in String::from_utf8_lossy(&content).lines() {
+        let mut parts = line.split_whitespace();
+        if let (Some(code), Some(unicode), None) = (parts.next(), parts.next(), parts.next()) {
+            if let (Some(code), Some(unicode)) = (hex(code), hex(unicode)) {
+                if let (Ok(code), Some(c)) = (u16::try_from(code), char::from_u32(unicode)) {
+                    map.insert(code, c.to_string());
+                }
+            }
+        }
+    }
+    map
+}
@@ -0,0 +1,31 @@
+use clap::Command;
+use coverletter::config::{file_settings, load_settings, write_settings};
+use std::{env, fs};
+
+// The only test in this binary, as it points HOME and the CV_* variables
+// at a scratch config for the whole process.
+#[test]
+fn write_settings_keeps_env_overrides_out_of_the_file() {
+    let home = tempfile::tempdir().unwrap();
+    let file = home.path().join(".cv.config.yaml");
+    fs::write(&file, "name: Alice\napi_base: http://localhost:11434\n").unwrap();
+    fs::write(home.path().join(".cvrc"), "CV_API_KEY=sk-from-cvrc\n").unwrap();
+    env::set_var("HOME", home.path());
+    env::set_var("CV_API_BASE", "http://evil");
+    env::set_var("CV_MODEL", "tmp");
+
+    let loaded = load_settings().unwrap();
+    assert_eq!(loaded.api_base.as_deref(), Some("http://evil"));
+    assert_eq!(loaded.model.as_deref(), Some("tmp"));
+    assert_eq!(loaded.api_key.as_deref(), Some("sk-from-cvrc"));
+
+    write_settings(&Command::new("cv"), Some("Bob".to_string()), None).unwrap();
+
+    let written = file_settings().unwrap();
+    assert_eq!(written.name.as_deref(), Some("Bob"));
+    assert_eq!(written.api_base.as_deref(), Some("http://localhost:11434"));
+    assert_eq!(written.model, None);
+    assert_eq!(written.api_key, None);
+    let text = fs::read_to_string(&file).unwrap();
+    assert!(!text.contains("evil") && !text.contains("sk-from-cvrc"));
+}
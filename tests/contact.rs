@@ -0,0 +1,22 @@
+use clap::{error::ErrorKind, Command};
+use coverletter::commands::contact;
+use std::{env, fs};
+
+// The only test in this binary, as it points HOME at a scratch config for
+// the whole process.
+#[test]
+fn contact_reports_a_bad_config_file() {
+    let home = tempfile::tempdir().unwrap();
+    let file = home.path().join(".cv.config.yaml");
+    fs::write(&file, "name: [unclosed\n").unwrap();
+    env::set_var("HOME", home.path());
+
+    let cmd = Command::new("contact").subcommand(Command::new("list"));
+    let matches = cmd.clone().get_matches_from(["contact", "list"]);
+    let err = contact(cmd, &matches).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Io);
+    assert!(err.to_string().contains("Unable to read ~/.cv.config.yaml"));
+
+    // The file is left for the user to fix
+    assert_eq!(fs::read_to_string(&file).unwrap(), "name: [unclosed\n");
+}
@@ -0,0 +1,264 @@
+use clap::ArgMatches;
+use coverletter::builders::{
+    docx,
+    utils::{ContactItem, ContactKind, Degree, Project, Skill, WorkExperience},
+    Settings,
+};
+use coverletter::config::{ConfigFile, Resume};
+use std::io::{Cursor, Read};
+
+fn settings() -> Settings {
+    Settings::load(&ConfigFile::default(), &ArgMatches::default()).unwrap()
+}
+
+fn contact() -> Vec<ContactItem> {
+    vec![
+        ContactItem {
+            kind: ContactKind::Email,
+            label: "jane@example.com".to_string(),
+            url: None,
+        },
+        ContactItem {
+            kind: ContactKind::Custom,
+            label: "Open to relocation".to_string(),
+            url: None,
+        },
+        ContactItem {
+            kind: ContactKind::Github,
+            label: "github.com/jane".to_string(),
+            url: None,
+        },
+    ]
+}
+
+fn resume() -> Resume {
+    Resume {
+        summary: Some("Builds **fast & reliable** systems.".to_string()),
+        skills: vec![Skill {
+            section: "Languages: ".to_string(),
+            skills: vec!["Rust".to_string(), "Go".to_string()],
+        }],
+        employment: vec![
+            WorkExperience {
+                position: "Senior Engineer".to_string(),
+                company: "Acme".to_string(),
+                location: "Remote".to_string(),
+                start_date: "2020".to_string(),
+                end_date: "Present".to_string(),
+                highlights: vec![
+                    "Cut CI time by **60%**.".to_string(),
+                    "Led <3> teams.".to_string(),
+                ],
+            },
+            WorkExperience {
+                position: "Engineer".to_string(),
+                company: "Initech".to_string(),
+                location: "Austin".to_string(),
+                start_date: "2017".to_string(),
+                end_date: "2019".to_string(),
+                highlights: vec!["Wrote pipelines.".to_string()],
+            },
+        ],
+        education: vec![Degree {
+            university: "State U".to_string(),
+            location: "Somewhere".to_string(),
+            degree: "BS".to_string(),
+            year: "2017".to_string(),
+            description: "Thesis on distributed systems".to_string(),
+        }],
+        projects: vec![Project {
+            nickname: None,
+            title: "Widget".to_string(),
+            organization: "Personal".to_string(),
+            year: "2021".to_string(),
+            description: None,
+            url: Some("https://example.com/widget?a=1&b=2".to_string()),
+        }],
+        ..Resume::default()
+    }
+}
+
+fn part(docx: &[u8], name: &str) -> String {
+    let mut archive = zip::ZipArchive::new(Cursor::new(docx)).expect("the output unzips");
+    let mut contents = String::new();
+    archive
+        .by_name(name)
+        .unwrap_or_else(|_| panic!("{} is missing", name))
+        .read_to_string(&mut contents)
+        .unwrap();
+    contents
+}
+
+/// Each paragraph's style and text, in order.
+fn paragraphs(document: &str) -> Vec<(String, String)> {
+    document
+        .split("<w:p>")
+        .skip(1)
+        .map(|p| {
+            let p = &p[..p.find("</w:p>").expect("paragraphs are closed")];
+            let style = p
+                .split("<w:pStyle w:val=\"")
+                .nth(1)
+                .and_then(|s| s.split('"').next())
+                .expect("every paragraph is styled")
+                .to_string();
+            let text = p
+                .split("<w:t xml:space=\"preserve\">")
+                .skip(1)
+                .map(|t| &t[..t.find("</w:t>").unwrap()])
+                .collect();
+            (style, text)
+        })
+        .collect()
+}
+
+/// The targets of the document's hyperlinks, in order.
+fn links(docx: &[u8], document: &str) -> Vec<String> {
+    let rels = part(docx, "word/_rels/document.xml.rels");
+    document
+        .split("<w:hyperlink r:id=\"")
+        .skip(1)
+        .map(|link| {
+            let id = &link[..link.find('"').unwrap()];
+            let rel = rels
+                .split("<Relationship ")
+                .find(|rel| rel.contains(&format!("Id=\"{}\"", id)))
+                .unwrap_or_else(|| panic!("{} has no relationship", id));
+            assert!(rel.contains("TargetMode=\"External\""));
+            let target = &rel[rel.find("Target=\"").unwrap() + 8..];
+            target[..target.find('"').unwrap()].to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn resume_has_headings_bullets_and_the_contact_block() {
+    let docx = docx::resume(
+        "Jane Doe",
+        "Platform Engineer",
+        &resume(),
+        &contact(),
+        &settings(),
+    )
+    .unwrap();
+
+    let types = part(&docx, "[Content_Types].xml");
+    for name in ["document", "styles", "numbering"] {
+        assert!(types.contains(&format!("PartName=\"/word/{}.xml\"", name)));
+    }
+    assert!(part(&docx, "_rels/.rels").contains("Target=\"word/document.xml\""));
+    let styles = part(&docx, "word/styles.xml");
+    for style in ["Title", "Heading1", "Heading2", "Contact", "ListBullet"] {
+        assert!(styles.contains(&format!("w:styleId=\"{}\"", style)));
+    }
+    assert!(part(&docx, "word/numbering.xml").contains("<w:numFmt w:val=\"bullet\"/>"));
+
+    let document = part(&docx, "word/document.xml");
+    assert!(document.contains("<w:body>") && document.contains("<w:sectPr>"));
+    let found = paragraphs(&document);
+    let found: Vec<(&str, &str)> = found
+        .iter()
+        .map(|(style, text)| (style.as_str(), text.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("Title", "JANE DOE"),
+            (
+                "Contact",
+                "jane@example.com | Open to relocation | github.com/jane"
+            ),
+            ("Heading1", "PLATFORM ENGINEER"),
+            ("Normal", "Builds fast &amp; reliable systems."),
+            ("Heading1", "SKILLS"),
+            ("Normal", "Languages: Rust, Go"),
+            ("Heading1", "EXPERIENCE"),
+            ("Heading2", "Senior Engineer, Acme"),
+            ("Normal", "Remote — 2020 - Present"),
+            ("ListBullet", "Cut CI time by 60%."),
+            ("ListBullet", "Led &lt;3&gt; teams."),
+            ("Heading2", "Engineer, Initech"),
+            ("Normal", "Austin — 2017 - 2019"),
+            ("ListBullet", "Wrote pipelines."),
+            ("Heading1", "EDUCATION"),
+            ("Heading2", "State U, BS"),
+            ("Normal", "Somewhere, 2017"),
+            ("Normal", "Thesis on distributed systems"),
+            ("Heading1", "PROJECTS"),
+            ("Heading2", "Widget"),
+            ("Normal", "Personal - 2021"),
+            ("Contact", "https://example.com/widget?a=1&amp;b=2"),
+        ]
+    );
+    // The summary's and highlights' emphasis is kept
+    assert!(document
+        .contains("<w:rPr><w:b/></w:rPr><w:t xml:space=\"preserve\">fast &amp; reliable</w:t>"));
+    assert!(document.contains("<w:rPr><w:b/></w:rPr><w:t xml:space=\"preserve\">60%</w:t>"));
+
+    assert_eq!(
+        links(&docx, &document),
+        [
+            "mailto:jane@example.com",
+            "https://github.com/jane",
+            "https://example.com/widget?a=1&amp;b=2",
+        ]
+    );
+}
+
+#[test]
+fn cover_letter_has_the_letter_and_contact_block() {
+    let body = vec![
+        "I'd like to work at Acme.".to_string(),
+        "Thank you.".to_string(),
+    ];
+    let docx = docx::cover_letter(
+        "Jane Doe",
+        "Acme",
+        Some("Austin, TX"),
+        Some("Ms. Smith"),
+        &body,
+        &contact(),
+        &settings(),
+    )
+    .unwrap();
+
+    let document = part(&docx, "word/document.xml");
+    let found = paragraphs(&document);
+    let styles: Vec<&str> = found.iter().map(|(style, _)| style.as_str()).collect();
+    let text: Vec<&str> = found.iter().map(|(_, text)| text.as_str()).collect();
+    assert_eq!(
+        styles,
+        [
+            "Label",
+            "LetterTitle",
+            "Normal",
+            "Normal",
+            "Normal",
+            "Normal",
+            "Normal",
+            "Normal",
+            "Normal",
+            "Label",
+            "Contact"
+        ]
+    );
+    assert_eq!(text[0], "FROM THE DESK OF");
+    assert_eq!(text[1], "JANE DOE");
+    assert_eq!(text[3], "AcmeAustin, TX");
+    assert!(document.contains("Acme</w:t></w:r><w:r><w:br/></w:r>"));
+    assert_eq!(
+        &text[4..9],
+        [
+            "Dear Ms. Smith,",
+            "I'd like to work at Acme.",
+            "Thank you.",
+            "Warm regards,",
+            "Jane Doe"
+        ]
+    );
+    assert_eq!(text[9], "CONTACT");
+    assert_eq!(
+        links(&docx, &document),
+        ["mailto:jane@example.com", "https://github.com/jane"]
+    );
+}
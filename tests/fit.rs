@@ -0,0 +1,79 @@
+use clap::ArgMatches;
+use coverletter::builders::{
+    fit,
+    paper::Paper,
+    resume,
+    utils::{Skill, WorkExperience},
+    Settings,
+};
+use coverletter::config::{ConfigFile, Resume};
+
+/// Letter paper, whatever the locale, so the page counts hold everywhere.
+fn settings() -> Settings {
+    let conf = ConfigFile {
+        paper: Some(Paper::Letter),
+        ..ConfigFile::default()
+    };
+    Settings::load(&conf, &ArgMatches::default()).unwrap()
+}
+
+/// A resume that runs a little onto a second page in the default theme.
+fn long_resume(jobs: usize) -> Resume {
+    Resume {
+        summary: Some("Platform engineer who keeps **large fleets** boring.".to_string()),
+        skills: vec![Skill {
+            section: "Languages: ".to_string(),
+            skills: vec!["Rust".to_string(), "Go".to_string(), "Python".to_string()],
+        }],
+        employment: (0..jobs)
+            .map(|i| WorkExperience {
+                position: "Platform Engineer".to_string(),
+                company: format!("Company {}", i + 1),
+                location: "Remote".to_string(),
+                start_date: format!("Jan {}", 2010 + i),
+                end_date: format!("Dec {}", 2010 + i),
+                highlights: (0..4)
+                    .map(|j| {
+                        format!(
+                            "Shipped project {} of {}, which cut deploy times and on-call pages \
+                             across the whole organisation by a good margin",
+                            j + 1,
+                            i + 1
+                        )
+                    })
+                    .collect(),
+            })
+            .collect(),
+        ..Resume::default()
+    }
+}
+
+#[test]
+fn fits_a_two_page_resume_onto_one() {
+    let settings = settings();
+    let resume = long_resume(6);
+    let unfitted =
+        resume::render("Jane Doe", "Platform Engineer", &resume, &[], &settings).unwrap();
+    assert_eq!(unfitted.pages, 2);
+
+    let fitted = fit::fit(
+        "Jane Doe",
+        "Platform Engineer",
+        &resume,
+        &[],
+        &settings,
+        1,
+        false,
+        "Platform Engineer",
+    )
+    .unwrap();
+    assert_eq!(fitted.rendered.pages, 1);
+    assert!(!fitted.changes.is_empty());
+    assert!(
+        fitted.changes[0].starts_with("line spacing"),
+        "{:?}",
+        fitted.changes
+    );
+    // Nothing was cut without --trim-highlights
+    assert!(fitted.changes.iter().all(|c| !c.starts_with("dropped")));
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use coverletter::config::{ConfigFile, Resume};
+use coverletter::generate::{
+    cover_letter_body, cover_letter_prompt, from_config, mock::Mock, openai::OpenAi,
+    CoverLetterRequest, Provider,
+};
+use serde_json::Value;
+
+static CHAT_REPLY: &str = r#"{"choices":[{"message":{"role":"assistant","content":"I build reliable systems.\n\nAcme's platform team is where I want to do it."}}]}"#;
+
+fn request(resume: &Resume) -> CoverLetterRequest<'_> {
+    CoverLetterRequest {
+        name: "Jane Doe",
+        company: "Acme",
+        position: Some("Platform Engineer"),
+        location: None,
+        hiring_manager: None,
+        job_description: None,
+        resume,
+    }
+}
+
+#[tokio::test]
+async fn openai_posts_to_the_configured_api_base() {
+    let (base, server) = common::serve_once("200 OK", "application/json", CHAT_REPLY).await;
+    let generator = OpenAi {
+        api_base: format!("{}/v1/", base),
+        api_key: "sk-test".to_string(),
+        model: "gpt-test".to_string(),
+    };
+    let resume = Resume::default();
+
+    let body = cover_letter_body(&generator, &request(&resume))
+        .await
+        .unwrap();
+    assert_eq!(
+        body,
+        vec![
+            "I build reliable systems.".to_string(),
+            "Acme's platform team is where I want to do it.".to_string(),
+        ]
+    );
+
+    let sent = server.await.unwrap();
+    assert_eq!(sent.method, "POST");
+    assert_eq!(sent.path, "/v1/chat/completions");
+    assert_eq!(
+        sent.headers.get("authorization").map(String::as_str),
+        Some("Bearer sk-test")
+    );
+    let json: Value = serde_json::from_str(&sent.body).unwrap();
+    assert_eq!(json["model"], "gpt-test");
+    let messages = json["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0]["role"], "system");
+    assert_eq!(messages[1]["role"], "user");
+    let prompt = messages[1]["content"].as_str().unwrap();
+    assert!(prompt.contains("from Jane Doe to Acme"));
+    assert!(prompt.contains("The position is: Platform Engineer."));
+}
+
+#[tokio::test]
+async fn mock_replies_the_same_way_every_time() {
+    let resume = Resume::default();
+    let req = request(&resume);
+    let words = cover_letter_prompt(&req).split_whitespace().count();
+
+    let first = cover_letter_body(&Mock, &req).await.unwrap();
+    let second = cover_letter_body(&Mock, &req).await.unwrap();
+    assert_eq!(first, second);
+    assert_eq!(
+        first,
+        vec![
+            "This is a mock reply to: Write the body of a cover letter from Jane Doe to Acme."
+                .to_string(),
+            format!("The prompt had {} words.", words),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn from_config_picks_the_mock_provider() {
+    let conf = ConfigFile {
+        provider: Some(Provider::Mock),
+        ..ConfigFile::default()
+    };
+    let generator = from_config(&conf).unwrap();
+    let reply = generator.generate("system", "Hello there").await.unwrap();
+    assert_eq!(
+        reply,
+        "This is a mock reply to: Hello there\n\nThe prompt had 2 words."
+    );
+}
+
+#[tokio::test]
+async fn from_config_picks_openai_by_default() {
+    let (base, server) = common::serve_once("200 OK", "application/json", CHAT_REPLY).await;
+    let conf = ConfigFile {
+        api_key: Some("sk-test".to_string()),
+        api_base: Some(base),
+        ..ConfigFile::default()
+    };
+    from_config(&conf)
+        .unwrap()
+        .generate("system", "prompt")
+        .await
+        .unwrap();
+
+    let sent = server.await.unwrap();
+    assert_eq!(
+        sent.headers.get("authorization").map(String::as_str),
+        Some("Bearer sk-test")
+    );
+    let json: Value = serde_json::from_str(&sent.body).unwrap();
+    assert_eq!(json["model"], "gpt-4o-mini");
+}
+
+#[test]
+fn openai_needs_an_api_key() {
+    for provider in [None, Some(Provider::Openai)] {
+        let conf = ConfigFile {
+            provider,
+            ..ConfigFile::default()
+        };
+        match from_config(&conf) {
+            Ok(_) => panic!("built an OpenAI generator without an API key"),
+            Err(e) => assert_eq!(e, "no API key has been set"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn ollama_sends_no_credentials() {
+    let (base, server) = common::serve_once("200 OK", "application/json", CHAT_REPLY).await;
+    let conf = ConfigFile {
+        provider: Some(Provider::Ollama),
+        // Set, but Ollama has no use for it
+        api_key: Some("sk-test".to_string()),
+        api_base: Some(format!("{}/v1", base)),
+        ..ConfigFile::default()
+    };
+    let reply = from_config(&conf)
+        .unwrap()
+        .generate("system", "prompt")
+        .await
+        .unwrap();
+    assert!(reply.starts_with("I build reliable systems."));
+
+    let sent = server.await.unwrap();
+    assert_eq!(sent.path, "/v1/chat/completions");
+    assert_eq!(sent.headers.get("authorization"), None);
+    let json: Value = serde_json::from_str(&sent.body).unwrap();
+    assert_eq!(json["model"], "llama3");
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use coverletter::job::{fetch, JobSource};
+
+#[tokio::test]
+async fn fetch_converts_html_postings_to_text() {
+    let (base, server) = common::serve_once(
+        "200 OK",
+        "text/html; charset=utf-8",
+        "<html><body><h1>Platform Engineer</h1>\n<p>We use <b>Rust</b> &amp; Go.</p>\
+         <ul><li>Kubernetes</li></ul><script>track()</script></body></html>",
+    )
+    .await;
+
+    let text = fetch(&format!("{}/jobs/42", base)).await.unwrap();
+    assert_eq!(text, "Platform Engineer\nWe use Rust & Go.\n- Kubernetes");
+
+    let sent = server.await.unwrap();
+    assert_eq!(sent.method, "GET");
+    assert_eq!(sent.path, "/jobs/42");
+}
+
+#[tokio::test]
+async fn fetch_keeps_plain_text_as_is() {
+    let (base, _server) =
+        common::serve_once("200 OK", "text/plain", "  Rust & <Go>\n\nKubernetes\n").await;
+    let source = JobSource::Url(format!("{}/jobs/7.txt", base));
+    assert_eq!(source.load().await.unwrap(), "Rust & <Go>\n\nKubernetes");
+}
+
+#[tokio::test]
+async fn fetch_fails_on_an_error_status() {
+    let (base, _server) = common::serve_once("404 Not Found", "text/html", "<p>Gone</p>").await;
+    let url = format!("{}/jobs/1", base);
+    assert_eq!(
+        fetch(&url).await,
+        Err(format!("{} returned 404 Not Found", url))
+    );
+}
@@ -0,0 +1,160 @@
+use coverletter::builders::{
+    font,
+    metadata::Metadata,
+    pages,
+    paper::Paper,
+    theme::Theme,
+    utils::{contact_links, projects, ContactItem, ContactKind, Project},
+};
+use genpdf::{Document, SimplePageDecorator};
+use lopdf::{content::Content, Object};
+
+fn contact() -> Vec<ContactItem> {
+    vec![
+        ContactItem {
+            kind: ContactKind::Email,
+            label: "jane@example.com".to_string(),
+            url: None,
+        },
+        ContactItem {
+            kind: ContactKind::Phone,
+            label: "+1 (555) 010-2000".to_string(),
+            url: None,
+        },
+        ContactItem {
+            kind: ContactKind::Github,
+            label: "github.com/jane".to_string(),
+            url: None,
+        },
+        ContactItem {
+            kind: ContactKind::Custom,
+            label: "Portfolio".to_string(),
+            url: Some("https://jane.example.com/work".to_string()),
+        },
+        ContactItem {
+            kind: ContactKind::Custom,
+            label: "Open to relocation".to_string(),
+            url: None,
+        },
+    ]
+}
+
+fn project(title: &str, url: Option<&str>) -> Project {
+    Project {
+        nickname: None,
+        title: title.to_string(),
+        organization: "Acme".to_string(),
+        year: "2023".to_string(),
+        description: None,
+        url: url.map(str::to_string),
+    }
+}
+
+/// The URI and rectangle of every link annotation, page by page.
+fn links(pdf: &[u8]) -> Vec<Vec<(String, Vec<f64>)>> {
+    let doc = lopdf::Document::load_mem(pdf).expect("the output parses");
+    doc.get_pages()
+        .into_values()
+        .map(|page| {
+            let page = doc.get_dictionary(page).unwrap();
+            let Ok(annots) = page.get(b"Annots").and_then(Object::as_array) else {
+                return Vec::new();
+            };
+            annots
+                .iter()
+                .map(|annot| {
+                    let annot = doc.get_dictionary(annot.as_reference().unwrap()).unwrap();
+                    assert_eq!(
+                        annot.get(b"Subtype").unwrap().as_name_str().unwrap(),
+                        "Link"
+                    );
+                    let action = annot.get(b"A").and_then(Object::as_dict).unwrap();
+                    assert_eq!(action.get(b"S").unwrap().as_name_str().unwrap(), "URI");
+                    let uri = action.get(b"URI").and_then(Object::as_str).unwrap();
+                    let rect = annot
+                        .get(b"Rect")
+                        .and_then(Object::as_array)
+                        .unwrap()
+                        .iter()
+                        .map(|v| v.as_f64().unwrap())
+                        .collect();
+                    (String::from_utf8(uri.to_vec()).unwrap(), rect)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn contact_items_and_project_urls_are_clickable() {
+    let theme = Theme::default();
+    let mut doc = Document::new(font::load(None).unwrap());
+    doc.set_paper_size(Paper::Letter);
+    doc.push(contact_links(&contact()));
+    doc.push(projects(
+        &theme,
+        vec![
+            project("Widget", Some("https://example.com/widget")),
+            project("Gadget", None),
+            project("Gizmo", Some("https://example.com/gizmo")),
+        ],
+    ));
+
+    let rendered = pages::render(doc, SimplePageDecorator::new(), &Metadata::default()).unwrap();
+    let pages = links(&rendered.pdf);
+
+    assert_eq!(pages.len(), 1);
+    let uris: Vec<&str> = pages[0].iter().map(|(uri, _)| uri.as_str()).collect();
+    assert_eq!(
+        uris,
+        [
+            "mailto:jane@example.com",
+            "tel:+15550102000",
+            "https://github.com/jane",
+            "https://jane.example.com/work",
+            "https://example.com/widget",
+            "https://example.com/gizmo",
+        ]
+    );
+
+    // Letter paper is 612 by 792pt
+    for (uri, rect) in &pages[0] {
+        let [left, bottom, right, top] = rect[..] else {
+            panic!("{} has a rectangle of {:?}", uri, rect);
+        };
+        assert!(0.0 <= left && left < right && right <= 612.0, "{}", uri);
+        assert!(0.0 <= bottom && bottom < top && top <= 792.0, "{}", uri);
+    }
+    // The contact items sit side by side on the first line
+    let contact = &pages[0][..4];
+    assert!(contact.windows(2).all(|pair| pair[0].1[2] <= pair[1].1[0]));
+    assert!(contact.iter().all(|(_, rect)| rect[1] == contact[0].1[1]));
+    // ...above the projects
+    assert!(pages[0][4].1[3] <= contact[0].1[1]);
+}
+
+#[test]
+fn link_markers_are_removed_from_the_page() {
+    let mut doc = Document::new(font::load(None).unwrap());
+    doc.push(contact_links(&contact()));
+
+    let rendered = pages::render(doc, SimplePageDecorator::new(), &Metadata::default()).unwrap();
+    let pdf = lopdf::Document::load_mem(&rendered.pdf).unwrap();
+    for page in pdf.get_pages().into_values() {
+        let content = Content::decode(&pdf.get_page_content(page).unwrap()).unwrap();
+        assert!(content
+            .operations
+            .iter()
+            .all(|op| !["RG", "m", "l", "S"].contains(&op.operator.as_str())));
+    }
+}
+
+#[test]
+fn documents_rendered_directly_have_no_links() {
+    let mut doc = Document::new(font::load(None).unwrap());
+    doc.push(contact_links(&contact()));
+
+    let mut pdf: Vec<u8> = Vec::new();
+    doc.render(&mut pdf).unwrap();
+    assert!(links(&pdf).iter().all(Vec::is_empty));
+}
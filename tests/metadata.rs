@@ -0,0 +1,105 @@
+use coverletter::builders::{font, metadata::Metadata, pages, utils::Skill};
+use coverletter::config::Resume;
+use genpdf::{elements::Paragraph, Document, SimplePageDecorator};
+use lopdf::Object;
+
+fn resume() -> Resume {
+    Resume {
+        skills: vec![
+            Skill {
+                section: "Languages: ".to_string(),
+                skills: vec!["Rust".to_string(), "Go".to_string()],
+            },
+            Skill {
+                section: "Tools: ".to_string(),
+                skills: vec!["rust".to_string(), "Docker".to_string()],
+            },
+        ],
+        ..Resume::default()
+    }
+}
+
+/// Renders a one-line PDF with `metadata` and reloads it.
+fn reload(metadata: &Metadata) -> lopdf::Document {
+    let mut doc = Document::new(font::load(None).unwrap());
+    doc.push(Paragraph::new("Hello"));
+    let rendered = pages::render(doc, SimplePageDecorator::new(), metadata).unwrap();
+    lopdf::Document::load_mem(&rendered.pdf).expect("the output parses")
+}
+
+/// The Info dictionary's `key`, decoding UTF-16 strings.
+fn info(doc: &lopdf::Document, key: &str) -> String {
+    let info = doc
+        .trailer
+        .get(b"Info")
+        .and_then(Object::as_reference)
+        .and_then(|id| doc.get_dictionary(id))
+        .unwrap();
+    let bytes = info.get(key.as_bytes()).and_then(Object::as_str).unwrap();
+    match bytes.strip_prefix(&[0xfe, 0xff]) {
+        Some(utf16) => String::from_utf16(
+            &utf16
+                .chunks(2)
+                .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+                .collect::<Vec<u16>>(),
+        )
+        .unwrap(),
+        None => String::from_utf8(bytes.to_vec()).unwrap(),
+    }
+}
+
+fn xmp(doc: &lopdf::Document) -> String {
+    let stream = doc
+        .catalog()
+        .and_then(|catalog| catalog.get(b"Metadata"))
+        .and_then(Object::as_reference)
+        .and_then(|id| doc.get_object(id))
+        .and_then(Object::as_stream)
+        .unwrap();
+    String::from_utf8(stream.content.clone()).unwrap()
+}
+
+#[test]
+fn metadata_lands_in_the_info_dictionary_and_xmp() {
+    let metadata = Metadata::resume("Zoë Doe", "Platform Engineer", Some("Acme"), &resume());
+    let doc = reload(&metadata);
+    let creator = format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+
+    assert_eq!(info(&doc, "Title"), "Zoë Doe - Resume");
+    assert_eq!(info(&doc, "Author"), "Zoë Doe");
+    assert_eq!(info(&doc, "Subject"), "Platform Engineer at Acme");
+    // Each skill once, whatever its case
+    assert_eq!(info(&doc, "Keywords"), "Rust, Go, Docker");
+    assert_eq!(info(&doc, "Creator"), creator);
+
+    let xmp = xmp(&doc);
+    assert_eq!(xmp.matches("<dc:title>").count(), 1);
+    for field in [
+        "<rdf:li xml:lang=\"x-default\">Zoë Doe - Resume</rdf:li>",
+        "<dc:creator>",
+        "<rdf:li>Zoë Doe</rdf:li>",
+        "<rdf:li xml:lang=\"x-default\">Platform Engineer at Acme</rdf:li>",
+        "<pdf:Keywords>Rust, Go, Docker</pdf:Keywords>",
+        &format!("<xmp:CreatorTool>{}</xmp:CreatorTool>", creator),
+    ] {
+        assert!(xmp.contains(field), "{} is missing from\n{}", field, xmp);
+    }
+}
+
+#[test]
+fn title_overrides_the_generated_one() {
+    let metadata = Metadata::cover_letter("Jane Doe", "R&D Labs", None, None)
+        .with_title(Some("Jane Doe <Application>"));
+    let doc = reload(&metadata);
+
+    assert_eq!(info(&doc, "Title"), "Jane Doe <Application>");
+    assert_eq!(info(&doc, "Subject"), "Application to R&D Labs");
+    let xmp = xmp(&doc);
+    assert!(xmp.contains("<rdf:li xml:lang=\"x-default\">Jane Doe &lt;Application&gt;</rdf:li>"));
+    assert!(xmp.contains("Application to R&amp;D Labs"));
+    assert!(!xmp.contains("Cover Letter for"));
+
+    // No title given keeps the generated one
+    let metadata = Metadata::cover_letter("Jane Doe", "Acme", None, None).with_title(None);
+    assert_eq!(metadata.title, "Jane Doe - Cover Letter for Acme");
+}
@@ -0,0 +1,70 @@
+use coverletter::builders::{font, metadata::Metadata, packet, pages};
+use genpdf::{elements::PageBreak, elements::Paragraph, Document, SimplePageDecorator};
+use lopdf::{Dictionary, Object, ObjectId};
+
+/// A PDF of `n` pages.
+fn pdf(n: usize) -> Vec<u8> {
+    let mut doc = Document::new(font::load(None).unwrap());
+    for page in 1..=n {
+        if page > 1 {
+            doc.push(PageBreak::new());
+        }
+        doc.push(Paragraph::new(format!("Page {}", page)));
+    }
+    let rendered = pages::render(doc, SimplePageDecorator::new(), &Metadata::default()).unwrap();
+    assert_eq!(rendered.pages, n);
+    rendered.pdf
+}
+
+fn dict<'a>(doc: &'a lopdf::Document, object: &Object) -> &'a Dictionary {
+    doc.get_dictionary(object.as_reference().unwrap()).unwrap()
+}
+
+fn reference(dict: &Dictionary, key: &[u8]) -> ObjectId {
+    dict.get(key).and_then(Object::as_reference).unwrap()
+}
+
+fn string(dict: &Dictionary, key: &[u8]) -> String {
+    String::from_utf8(dict.get(key).and_then(Object::as_str).unwrap().to_vec()).unwrap()
+}
+
+#[test]
+fn merge_joins_the_parts_with_a_bookmark_each() {
+    let letter = pdf(1);
+    let resume = pdf(2);
+    let metadata = Metadata {
+        title: "Jane Doe - Application to Acme".to_string(),
+        author: "Jane Doe".to_string(),
+        subject: "Engineer at Acme".to_string(),
+        keywords: vec!["Rust".to_string(), "Go".to_string()],
+    };
+
+    let merged =
+        packet::merge(&[("Cover Letter", &letter), ("Resume", &resume)], &metadata).unwrap();
+    let doc = lopdf::Document::load_mem(&merged).expect("the merged PDF parses");
+
+    let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    assert_eq!(pages.len(), 3);
+
+    let catalog = doc.catalog().unwrap();
+    let outlines = dict(&doc, catalog.get(b"Outlines").unwrap());
+    assert_eq!(outlines.get(b"Count").unwrap().as_i64().unwrap(), 2);
+    let first = dict(&doc, outlines.get(b"First").unwrap());
+    let last = dict(&doc, outlines.get(b"Last").unwrap());
+    assert_eq!(string(first, b"Title"), "Cover Letter");
+    assert_eq!(string(last, b"Title"), "Resume");
+    assert_eq!(reference(first, b"Next"), reference(outlines, b"Last"));
+    assert_eq!(reference(last, b"Prev"), reference(outlines, b"First"));
+
+    // Each bookmark opens the first page of its part
+    for (item, page) in [(first, pages[0]), (last, pages[1])] {
+        let dest = item.get(b"Dest").and_then(Object::as_array).unwrap();
+        assert_eq!(dest[0].as_reference().unwrap(), page);
+    }
+
+    let info = dict(&doc, doc.trailer.get(b"Info").unwrap());
+    assert_eq!(string(info, b"Title"), metadata.title);
+    assert_eq!(string(info, b"Author"), "Jane Doe");
+    assert_eq!(string(info, b"Subject"), "Engineer at Acme");
+    assert_eq!(string(info, b"Keywords"), "Rust, Go");
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use clap::ArgMatches;
+use coverletter::builders::{
+    paper::Paper,
+    resume,
+    utils::{ContactItem, ContactKind, WorkExperience},
+    Settings,
+};
+use coverletter::config::{ConfigFile, Resume};
+
+fn settings() -> Settings {
+    let conf = ConfigFile {
+        paper: Some(Paper::Letter),
+        ..ConfigFile::default()
+    };
+    Settings::load(&conf, &ArgMatches::default()).unwrap()
+}
+
+fn contact() -> Vec<ContactItem> {
+    vec![ContactItem {
+        kind: ContactKind::Email,
+        label: "jane@example.com".to_string(),
+        url: None,
+    }]
+}
+
+/// A resume of `jobs` jobs; six run onto a second page of Letter paper.
+fn cv(jobs: usize) -> Resume {
+    Resume {
+        employment: (0..jobs)
+            .map(|i| WorkExperience {
+                position: "Platform Engineer".to_string(),
+                company: format!("Company {}", i + 1),
+                location: "Remote".to_string(),
+                start_date: format!("Jan {}", 2010 + i),
+                end_date: format!("Dec {}", 2010 + i),
+                highlights: (0..4)
+                    .map(|j| {
+                        format!(
+                            "Shipped project {} of {}, which cut deploy times and on-call pages \
+                             across the whole organisation by a good margin",
+                            j + 1,
+                            i + 1
+                        )
+                    })
+                    .collect(),
+            })
+            .collect(),
+        ..Resume::default()
+    }
+}
+
+fn render(resume: &Resume, settings: &Settings) -> Vec<String> {
+    let rendered = resume::render(
+        "Jane Doe",
+        "Platform Engineer",
+        resume,
+        &contact(),
+        settings,
+    )
+    .unwrap();
+    let text = common::page_text(&rendered.pdf);
+    assert_eq!(text.len(), rendered.pages);
+    text
+}
+
+#[test]
+fn long_documents_number_their_pages() {
+    let pages = render(&cv(6), &settings());
+    assert_eq!(pages.len(), 2);
+    assert!(pages[0].contains("Page 1 of 2"), "{}", pages[0]);
+    assert!(pages[1].contains("Page 2 of 2"), "{}", pages[1]);
+    // The name and contact line are repeated on the second page only
+    assert_eq!(pages[0].matches("jane@example.com").count(), 1);
+    let header: Vec<&str> = pages[1].lines().skip(1).take(3).collect();
+    assert_eq!(header, ["JANE DOE", "  |  ", "jane@example.com"]);
+}
+
+#[test]
+fn one_page_documents_have_no_footer() {
+    let pages = render(&cv(1), &settings());
+    assert_eq!(pages.len(), 1);
+    assert!(!pages[0].contains("Page 1"), "{}", pages[0]);
+}
+
+#[test]
+fn page_format_placeholders_are_filled_in() {
+    let mut settings = settings();
+    settings.pages.resume.page_format = "{page}/{pages} - Jane".to_string();
+    let pages = render(&cv(6), &settings);
+    assert!(pages[0].contains("1/2 - Jane"), "{}", pages[0]);
+    assert!(pages[1].contains("2/2 - Jane"), "{}", pages[1]);
+    assert!(pages.iter().all(|page| !page.contains('{')));
+
+    settings.pages.resume.page_numbers = false;
+    let pages = render(&cv(6), &settings);
+    assert!(pages.iter().all(|page| !page.contains("/2 - Jane")));
+}
@@ -0,0 +1,86 @@
+use coverletter::builders::{
+    font,
+    theme::Theme,
+    utils::{grid, projects, Project},
+};
+use genpdf::Document;
+
+fn project(i: usize) -> Project {
+    // Matching on the remainder rather than `is_multiple_of`, which needs
+    // Rust 1.87
+    Project {
+        nickname: match i % 2 {
+            0 => Some(format!("P{}", i)),
+            _ => None,
+        },
+        title: format!("Project {}", i),
+        organization: "Acme".to_string(),
+        year: "2023".to_string(),
+        description: match i % 3 {
+            0 => Some(format!("Built **thing {}**.", i)),
+            _ => None,
+        },
+        url: match i % 4 {
+            0 => Some(format!("https://example.com/{}", i)),
+            _ => None,
+        },
+    }
+}
+
+#[test]
+fn grid_keeps_every_project_once_in_order() {
+    for columns in 1..=4 {
+        for n in 0..=10 {
+            let items: Vec<usize> = (0..n).collect();
+            let rows = grid(&items, columns);
+
+            assert_eq!(rows.len(), n.div_ceil(columns), "{} in {}", n, columns);
+            assert!(rows.iter().all(|row| row.len() == columns));
+
+            let cells: Vec<usize> = rows.iter().flatten().flatten().copied().copied().collect();
+            assert_eq!(cells, items, "{} projects in {} columns", n, columns);
+        }
+    }
+}
+
+#[test]
+fn grid_pads_only_the_last_row() {
+    for n in 0..=10 {
+        let items: Vec<usize> = (0..n).collect();
+        let rows = grid(&items, 3);
+        for (i, row) in rows.iter().enumerate() {
+            let filled = row.iter().filter(|cell| cell.is_some()).count();
+            if i + 1 < rows.len() {
+                assert_eq!(filled, 3);
+            } else {
+                assert_eq!(filled, n - 3 * i);
+                // Padding trails the projects
+                assert!(row[..filled].iter().all(Option::is_some));
+            }
+        }
+    }
+}
+
+#[test]
+fn grid_treats_zero_columns_as_one() {
+    let rows = grid(&[1, 2], 0);
+    assert_eq!(rows, vec![vec![Some(&1)], vec![Some(&2)]]);
+}
+
+#[test]
+fn projects_render_for_0_to_10_projects() {
+    // Embedding the fonts dominates the render time, so each column count
+    // gets one document holding a grid for every project count.
+    for columns in [1, 3, 4] {
+        let mut theme = Theme::default();
+        theme.layout.project_columns = columns;
+        let mut doc = Document::new(font::load(None).expect("bundled fonts load"));
+        for n in 0..=10 {
+            doc.push(projects(&theme, (0..n).map(project).collect()));
+        }
+        let mut pdf: Vec<u8> = Vec::new();
+        doc.render(&mut pdf)
+            .unwrap_or_else(|e| panic!("{} columns: {}", columns, e));
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+}